@@ -4,12 +4,13 @@ use std::{
     fs::{self, File},
     hash::{Hash, Hasher},
     io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::cli::Config;
+use crate::cli::{Config, ImageBackend};
 
 #[derive(Deserialize, Serialize)]
 pub struct Cache {
@@ -17,21 +18,45 @@ pub struct Cache {
     max_width: u8,
     alpha_threshold: u8,
     aliasing: bool,
+    backend: ImageBackend,
+    ascii_colors: bool,
     pub image: String,
 }
 
+#[derive(Deserialize, Serialize)]
+struct PublicIpCache {
+    url: String,
+    ip: String,
+    timestamp: u64,
+}
+
 #[inline]
-fn cache_path() -> Option<String> {
+fn cache_path() -> Option<PathBuf> {
+    Some(cache_dir()?.join("cache.toml"))
+}
+
+/// The directory pixfetch keeps its cache files in
+pub fn cache_dir() -> Option<PathBuf> {
     if let Ok(xdg_home) = env::var("XDG_CACHE_HOME") {
-        Some(format!("{}/pixfetch/cache.toml", xdg_home))
+        Some(Path::new(&xdg_home).join("pixfetch"))
     } else if let Ok(home) = env::var("HOME") {
-        Some(format!("{}/.cache/pixfetch/cache.toml", home))
+        Some(Path::new(&home).join(".cache/pixfetch"))
     } else {
         None
     }
 }
 
-pub fn read_cache(config: &Config, image: &[u8]) -> Option<Cache> {
+#[inline]
+fn now() -> Option<u64> {
+    Some(SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+pub fn read_cache(
+    config: &Config,
+    backend: ImageBackend,
+    ascii_colors: bool,
+    image: &[u8],
+) -> Option<Cache> {
     if config.skip_cache.unwrap_or(crate::DEFAULT_SKIP_CACHE) {
         return None;
     }
@@ -49,6 +74,8 @@ pub fn read_cache(config: &Config, image: &[u8]) -> Option<Cache> {
                 .alpha_threshold
                 .unwrap_or(crate::DEFAULT_ALPHA_THRESHOLD)
         || c.aliasing != config.aliasing.unwrap_or(crate::DEFAULT_ALIASING)
+        || c.backend != backend
+        || c.ascii_colors != ascii_colors
     {
         return None;
     }
@@ -62,7 +89,13 @@ pub fn read_cache(config: &Config, image: &[u8]) -> Option<Cache> {
     Some(c)
 }
 
-pub fn write_cache(config: &Config, image: &[u8], image_str: String) -> Option<()> {
+pub fn write_cache(
+    config: &Config,
+    backend: ImageBackend,
+    ascii_colors: bool,
+    image: &[u8],
+    image_str: String,
+) -> Option<()> {
     if config.skip_cache.unwrap_or(crate::DEFAULT_SKIP_CACHE) {
         return None;
     }
@@ -78,11 +111,73 @@ pub fn write_cache(config: &Config, image: &[u8], image_str: String) -> Option<(
             .alpha_threshold
             .unwrap_or(crate::DEFAULT_ALPHA_THRESHOLD),
         aliasing: config.aliasing.unwrap_or(crate::DEFAULT_ALIASING),
+        backend,
+        ascii_colors,
         image: image_str,
     };
 
-    fs::create_dir_all(Path::new(&path).parent().unwrap()).ok()?;
+    fs::create_dir_all(path.parent().unwrap()).ok()?;
     let mut file = File::create(path).ok()?;
     file.write_all(toml::to_string(&c).ok()?.as_bytes()).ok()?;
     Some(())
 }
+
+/// The public IP from the given URL cached in `dir`, if it is younger than `ttl` seconds
+pub fn read_public_ip(dir: &Path, url: &str, ttl: u64) -> Option<String> {
+    let buf = fs::read_to_string(dir.join("public_ip.toml")).ok()?;
+    let c = toml::from_str::<PublicIpCache>(&buf).ok()?;
+    if c.url != url || now()?.saturating_sub(c.timestamp) >= ttl {
+        return None;
+    }
+    Some(c.ip)
+}
+
+pub fn write_public_ip(dir: &Path, url: &str, ip: &str) -> Option<()> {
+    let path = dir.join("public_ip.toml");
+    let c = PublicIpCache {
+        url: url.to_string(),
+        ip: ip.to_string(),
+        timestamp: now()?,
+    };
+
+    fs::create_dir_all(dir).ok()?;
+    let mut file = File::create(path).ok()?;
+    file.write_all(toml::to_string(&c).ok()?.as_bytes()).ok()?;
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn public_ip_ttl() {
+        let cache = FakeRoot::new("public-ip-cache");
+        let url = "http://ip.example/";
+
+        assert_eq!(read_public_ip(cache.path(), url, 3600), None);
+        write_public_ip(cache.path(), url, "203.0.113.7").unwrap();
+        assert_eq!(
+            read_public_ip(cache.path(), url, 3600).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            read_public_ip(cache.path(), "http://other.example/", 3600),
+            None
+        );
+        assert_eq!(read_public_ip(cache.path(), url, 0), None);
+
+        let expired = PublicIpCache {
+            url: url.to_string(),
+            ip: "203.0.113.7".to_string(),
+            timestamp: now().unwrap() - 7200,
+        };
+        cache.file("public_ip.toml", toml::to_string(&expired).unwrap());
+        assert_eq!(read_public_ip(cache.path(), url, 3600), None);
+        assert_eq!(
+            read_public_ip(cache.path(), url, 10800).as_deref(),
+            Some("203.0.113.7")
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use clap::{builder::ValueHint, Parser};
-use serde::Deserialize;
+use std::{collections::HashMap, convert::Infallible, ffi::OsStr, str::FromStr};
+
+use clap::{
+    builder::{StringValueParser, TypedValueParser, ValueHint},
+    Arg, Command, Parser, PossibleValue, ValueEnum,
+};
+use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString};
 
 #[derive(Debug, Deserialize, Parser)]
@@ -47,6 +52,27 @@ pub struct Config {
     #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath, action)]
     pub image_override: Option<String>,
 
+    // TODO: get completion for more than one value (possible with https://github.com/clap-rs/clap/issues/3166)
+    /// The infos to show in the order they should appear in
+    ///
+    /// - Either use the option multiple times, or seperate the items with commas
+    ///
+    /// - The same info may be listed multiple times
+    ///
+    /// - The whitelist and blacklist are still applied afterwards
+    ///
+    /// - Custom infos are referenced by their name
+    #[clap(
+        long,
+        use_value_delimiter = true,
+        require_value_delimiter = true,
+        value_name = "INFOS",
+        min_values = 0,
+        value_parser = InfoEntryParser,
+        action
+    )]
+    pub info_order: Option<Vec<InfoEntry>>,
+
     // TODO: get completion for more than one value (possible with https://github.com/clap-rs/clap/issues/3166)
     /// A list of infos to exclusively show
     ///
@@ -57,9 +83,10 @@ pub struct Config {
         require_value_delimiter = true,
         value_name = "INFOS",
         min_values = 0,
+        value_parser = InfoEntryParser,
         action
     )]
-    pub info_whitelist: Option<Vec<Info>>,
+    pub info_whitelist: Option<Vec<InfoEntry>>,
 
     // TODO: get completion for more than one value (possible with https://github.com/clap-rs/clap/issues/3166)
     /// A list of infos to not show
@@ -71,9 +98,232 @@ pub struct Config {
         require_value_delimiter = true,
         value_name = "INFOS",
         min_values = 0,
+        value_parser = InfoEntryParser,
         action
     )]
-    pub info_blacklist: Option<Vec<Info>>,
+    pub info_blacklist: Option<Vec<InfoEntry>>,
+
+    /// The output format
+    ///
+    /// - `pretty` prints the image next to the colored infos
+    ///
+    /// - `json` skips the image and prints all infos with their raw values as JSON
+    #[clap(long, value_name = "FORMAT", value_enum, action)]
+    pub format: Option<Format>,
+
+    /// The maximum time in milliseconds to wait for the infos, which are collected in parallel
+    ///
+    /// - Must be an integer greater than 0
+    ///
+    /// - Commands still running afterwards are killed
+    #[clap(long, value_name = "MILLIS", value_parser = clap::value_parser!(u64).range(1..), action)]
+    pub info_timeout: Option<u64>,
+
+    /// The text to show for infos that took longer than the timeout
+    ///
+    /// - When not set, these infos are left out
+    #[clap(long, value_name = "TEXT", action)]
+    pub timeout_placeholder: Option<String>,
+
+    /// How to draw the image
+    ///
+    /// - `auto` uses the best protocol the terminal is known to support
+    ///
+    /// - `ansi` draws colored half block characters
+    ///
+    /// - `kitty` and `sixel` draw the image in full resolution using the respective graphics protocol
+    ///
+    /// - `ascii` draws the image as ASCII art, which is also used without colors when `--color` is `never`
+    #[clap(long, value_name = "BACKEND", value_enum, action)]
+    pub image_backend: Option<ImageBackend>,
+
+    /// Whether to color the ASCII art of the `ascii` image backend
+    #[clap(long, value_name = "true|false", action)]
+    pub ascii_colors: Option<bool>,
+
+    /// When to use colors and show the image
+    ///
+    /// - `auto` uses colors when writing to a terminal and the `NO_COLOR` environment variable is not set
+    ///
+    /// - `never` prints plain `Key: value` lines without the image, or with uncolored ASCII art when
+    ///   using the `ascii` image backend
+    #[clap(long, value_name = "WHEN", value_enum, action)]
+    pub color: Option<ColorMode>,
+
+    /// The language of the builtin info labels, like `de` or `fr`
+    ///
+    /// - Defaults to the language of the current locale
+    #[clap(long, value_name = "LANGUAGE", action)]
+    pub language: Option<String>,
+
+    /// The unit system for sizes like memory usage
+    ///
+    /// - `iec` uses powers of 1024 (KiB, MiB, GiB, TiB)
+    ///
+    /// - `si` uses powers of 1000 (kB, MB, GB, TB)
+    #[clap(long, value_name = "SYSTEM", value_enum, action)]
+    pub unit_system: Option<UnitSystem>,
+
+    /// Whether to show the used percentage of resources like memory
+    #[clap(long, value_name = "true|false", action)]
+    pub show_percent: Option<bool>,
+
+    /// The mount points to show the disk usage of, mount points that do not exist are skipped
+    #[clap(
+        long,
+        use_value_delimiter = true,
+        require_value_delimiter = true,
+        value_name = "PATHS",
+        min_values = 0,
+        action
+    )]
+    pub disk_mounts: Option<Vec<String>>,
+
+    /// Name prefixes of network interfaces to hide, like `lo` for the loopback interface
+    #[clap(
+        long,
+        use_value_delimiter = true,
+        require_value_delimiter = true,
+        value_name = "PREFIXES",
+        min_values = 0,
+        action
+    )]
+    pub hidden_interfaces: Option<Vec<String>>,
+
+    /// A plain `http://` URL responding with the public IP, like `http://ifconfig.me/ip`
+    ///
+    /// - The `PublicIP` info is only shown when this is set
+    #[clap(long, value_name = "URL", action)]
+    pub public_ip_url: Option<String>,
+
+    /// The time in seconds to cache the public IP for, `0` disables the cache
+    #[clap(long, value_name = "SECONDS", action)]
+    pub public_ip_ttl: Option<u64>,
+
+    /// Whether to show the number of failed systemd units in the `Init` info
+    #[clap(long, value_name = "true|false", action)]
+    pub show_failed_units: Option<bool>,
+
+    /// Which shell to show
+    ///
+    /// - `current` is the shell pixfetch was started from
+    ///
+    /// - `login` is the login shell from `$SHELL`
+    #[clap(long, value_name = "SHELL", value_enum, action)]
+    pub shell: Option<ShellMode>,
+
+    // the fields only configurable in the config file are not read by the build script
+    /// Custom labels for infos by their name, only configurable in the config file
+    #[allow(dead_code)]
+    #[clap(skip)]
+    pub labels: Option<HashMap<String, String>>,
+
+    /// Templates for the values of infos by their name, only configurable in the config file
+    #[allow(dead_code)]
+    #[clap(skip)]
+    pub templates: Option<HashMap<String, String>>,
+
+    /// Additional infos defined by a command or file, only configurable in the config file
+    #[allow(dead_code)]
+    #[clap(skip)]
+    pub custom_infos: Option<Vec<CustomInfo>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageBackend {
+    Auto,
+    Ansi,
+    Ascii,
+    Kitty,
+    Sixel,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Iec,
+    Si,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellMode {
+    Current,
+    Login,
+}
+
+/// An info whose value is the trimmed output of a shell command or the trimmed contents of a file
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct CustomInfo {
+    pub name: String,
+    pub command: Option<String>,
+    pub file: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Pretty,
+    Json,
+}
+
+/// Either a builtin info or the name of a custom info
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(untagged)]
+pub enum InfoEntry {
+    Builtin(Info),
+    Custom(String),
+}
+
+impl FromStr for InfoEntry {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<Info>() {
+            Ok(info) => Self::Builtin(info),
+            Err(_) => Self::Custom(s.to_owned()),
+        })
+    }
+}
+
+/// Parses any string as an [`InfoEntry`] while still listing the builtin infos as possible values
+/// in the help and the completions
+#[derive(Clone)]
+pub struct InfoEntryParser;
+
+impl TypedValueParser for InfoEntryParser {
+    type Value = InfoEntry;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let value = StringValueParser::new().parse_ref(cmd, arg, value)?;
+        Ok(match value.parse() {
+            Ok(entry) => entry,
+            Err(infallible) => match infallible {},
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue<'static>> + '_>> {
+        Some(Box::new(
+            Info::value_variants()
+                .iter()
+                .filter_map(ValueEnum::to_possible_value),
+        ))
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter)]
@@ -82,14 +332,31 @@ pub enum Info {
     UserAtHostname,
     OS,
     Host,
+    Virtualization,
     Kernel,
     Uptime,
+    Init,
+    Load,
+    Processes,
+    Users,
     Packages,
     Shell,
+    DE,
+    WM,
+    Session,
+    Theme,
+    Icons,
+    Cursor,
+    Font,
     Terminal,
     CPU,
+    GPU,
     Memory,
     Swap,
+    Disk,
+    LocalIP,
+    PublicIP,
+    Network,
     Battery,
     Seperator,
     Colors1,
@@ -102,14 +369,31 @@ impl clap::ValueEnum for Info {
             Self::UserAtHostname,
             Self::OS,
             Self::Host,
+            Self::Virtualization,
             Self::Kernel,
             Self::Uptime,
+            Self::Init,
+            Self::Load,
+            Self::Processes,
+            Self::Users,
             Self::Packages,
             Self::Shell,
+            Self::DE,
+            Self::WM,
+            Self::Session,
+            Self::Theme,
+            Self::Icons,
+            Self::Cursor,
+            Self::Font,
             Self::Terminal,
             Self::CPU,
+            Self::GPU,
             Self::Memory,
             Self::Swap,
+            Self::Disk,
+            Self::LocalIP,
+            Self::PublicIP,
+            Self::Network,
             Self::Battery,
             Self::Seperator,
             Self::Colors1,
@@ -122,14 +406,31 @@ impl clap::ValueEnum for Info {
             Self::UserAtHostname => Some(clap::PossibleValue::new("UserAtHostname")),
             Self::OS => Some(clap::PossibleValue::new("OS")),
             Self::Host => Some(clap::PossibleValue::new("Host")),
+            Self::Virtualization => Some(clap::PossibleValue::new("Virtualization")),
             Self::Kernel => Some(clap::PossibleValue::new("Kernel")),
             Self::Uptime => Some(clap::PossibleValue::new("Uptime")),
+            Self::Init => Some(clap::PossibleValue::new("Init")),
+            Self::Load => Some(clap::PossibleValue::new("Load")),
+            Self::Processes => Some(clap::PossibleValue::new("Processes")),
+            Self::Users => Some(clap::PossibleValue::new("Users")),
             Self::Packages => Some(clap::PossibleValue::new("Packages")),
             Self::Shell => Some(clap::PossibleValue::new("Shell")),
+            Self::DE => Some(clap::PossibleValue::new("DE")),
+            Self::WM => Some(clap::PossibleValue::new("WM")),
+            Self::Session => Some(clap::PossibleValue::new("Session")),
+            Self::Theme => Some(clap::PossibleValue::new("Theme")),
+            Self::Icons => Some(clap::PossibleValue::new("Icons")),
+            Self::Cursor => Some(clap::PossibleValue::new("Cursor")),
+            Self::Font => Some(clap::PossibleValue::new("Font")),
             Self::Terminal => Some(clap::PossibleValue::new("Terminal")),
             Self::CPU => Some(clap::PossibleValue::new("CPU")),
+            Self::GPU => Some(clap::PossibleValue::new("GPU")),
             Self::Memory => Some(clap::PossibleValue::new("Memory")),
             Self::Swap => Some(clap::PossibleValue::new("Swap")),
+            Self::Disk => Some(clap::PossibleValue::new("Disk")),
+            Self::LocalIP => Some(clap::PossibleValue::new("LocalIP")),
+            Self::PublicIP => Some(clap::PossibleValue::new("PublicIP")),
+            Self::Network => Some(clap::PossibleValue::new("Network")),
             Self::Battery => Some(clap::PossibleValue::new("Battery")),
             Self::Seperator => Some(clap::PossibleValue::new("Seperator")),
             Self::Colors1 => Some(clap::PossibleValue::new("Colors1")),
@@ -0,0 +1,92 @@
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+    sync::{mpsc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The IDs of all commands which are still running
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Runs a command and returns its stdout, or `None` if it failed or did not exit before the
+/// deadline
+///
+/// On unix, the command runs in its own process group, so that processes started by it are
+/// killed together with it when the deadline is reached.
+pub fn run(command: &mut Command, deadline: Instant) -> Option<String> {
+    if Instant::now() >= deadline {
+        return None;
+    }
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let id = child.id();
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner()).push(id);
+    let output = wait(&mut child, deadline);
+    RUNNING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|running| *running != id);
+    output
+}
+
+fn wait(child: &mut Child, deadline: Instant) -> Option<String> {
+    // read in a separate thread, so a full pipe cannot block the child
+    let mut stdout = child.stdout.take()?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = vec![];
+        sender.send(stdout.read_to_end(&mut buf).map(|_| buf)).ok();
+    });
+
+    loop {
+        match child.try_wait().ok()? {
+            Some(status) if status.success() => break,
+            Some(_) => return None,
+            None if Instant::now() >= deadline => {
+                kill(child.id());
+                child.wait().ok();
+                return None;
+            }
+            None => thread::sleep(Duration::from_millis(5)),
+        }
+    }
+
+    // the output might still be held open by a background process of the command
+    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(stdout) => Some(String::from_utf8_lossy(&stdout.ok()?).into_owned()),
+        Err(_) => {
+            kill(child.id());
+            None
+        }
+    }
+}
+
+/// Kills all commands which are still running, so none are left behind when pixfetch exits
+pub fn kill_running() {
+    for id in RUNNING.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        kill(*id);
+    }
+}
+
+#[cfg(unix)]
+fn kill(id: u32) {
+    // the negative ID addresses the whole process group
+    unsafe { libc::kill(-(id as libc::pid_t), libc::SIGKILL) };
+}
+
+#[cfg(not(unix))]
+fn kill(id: u32) {
+    Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &id.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok();
+}
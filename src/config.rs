@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     env,
     fs::{self, File},
     io::{self, Read, Write},
@@ -6,9 +7,12 @@ use std::{
     process,
 };
 
+use strum::IntoEnumIterator;
+
 use crate::{
-    cli::{Config, Info},
+    cli::{Config, CustomInfo, Info, InfoEntry},
     error::Error,
+    locale,
 };
 
 impl Default for Config {
@@ -22,8 +26,33 @@ impl Default for Config {
             gap: None,
             image_override: None,
             color_override: None,
+            info_order: Some(
+                crate::DEFAULT_INFO_ORDER
+                    .iter()
+                    .copied()
+                    .map(InfoEntry::Builtin)
+                    .collect(),
+            ),
             info_whitelist: None,
-            info_blacklist: Some(vec![Info::Terminal]),
+            info_blacklist: None,
+            format: None,
+            info_timeout: None,
+            timeout_placeholder: None,
+            image_backend: None,
+            ascii_colors: None,
+            color: None,
+            language: None,
+            unit_system: None,
+            show_percent: None,
+            disk_mounts: None,
+            hidden_interfaces: None,
+            public_ip_url: None,
+            public_ip_ttl: None,
+            show_failed_units: None,
+            shell: None,
+            labels: None,
+            templates: None,
+            custom_infos: None,
         }
     }
 }
@@ -61,8 +90,128 @@ impl Config {
                 )));
             }
         }
+        if self.info_timeout == Some(0) {
+            return Err(Error::InvalidConfig(
+                "The specified info_timeout must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(url) = &self.public_ip_url {
+            if !url.starts_with("http://") {
+                return Err(Error::InvalidConfig(format!(
+                    "The specified public_ip_url `{url}` does not start with `http://`"
+                )));
+            }
+        }
+        if let Some(custom_infos) = &self.custom_infos {
+            for (index, custom) in custom_infos.iter().enumerate() {
+                // names are compared case-insensitively, so labels and templates stay unambiguous
+                if Info::iter().any(|info| format!("{:?}", info).eq_ignore_ascii_case(&custom.name))
+                {
+                    return Err(Error::InvalidConfig(format!(
+                        "The custom info name `{}` is already used by a builtin info",
+                        custom.name,
+                    )));
+                }
+                if custom_infos[..index]
+                    .iter()
+                    .any(|c| c.name.eq_ignore_ascii_case(&custom.name))
+                {
+                    return Err(Error::InvalidConfig(format!(
+                        "The custom info name `{}` is used more than once",
+                        custom.name,
+                    )));
+                }
+                if custom.command.is_some() == custom.file.is_some() {
+                    return Err(Error::InvalidConfig(format!(
+                        "The custom info `{}` must specify exactly one of `command` or `file`",
+                        custom.name,
+                    )));
+                }
+            }
+        }
+        for list in [&self.info_order, &self.info_whitelist, &self.info_blacklist]
+            .into_iter()
+            .flatten()
+        {
+            for entry in list {
+                if let InfoEntry::Custom(name) = entry {
+                    if self.custom_info(name).is_none() {
+                        return Err(Error::InvalidConfig(format!(
+                            "The info `{}` is neither a builtin nor a custom info",
+                            name,
+                        )));
+                    }
+                }
+            }
+        }
+        for (table, kind) in [(&self.labels, "label"), (&self.templates, "template")] {
+            let names: Vec<_> = table.iter().flat_map(|table| table.keys()).collect();
+            for (index, name) in names.iter().enumerate() {
+                if !self.is_info_name(name) {
+                    return Err(Error::InvalidConfig(format!(
+                        "Cannot set a {} for `{}`, as it is neither a builtin nor a custom info",
+                        kind, name,
+                    )));
+                }
+                if names[..index].iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    return Err(Error::InvalidConfig(format!(
+                        "The {} for `{}` is set more than once",
+                        kind, name,
+                    )));
+                }
+            }
+        }
         Ok(self)
     }
+
+    /// Whether a key in the `labels` or `templates` table names a builtin or custom info,
+    /// ignoring its case
+    fn is_info_name(&self, name: &str) -> bool {
+        Info::iter().any(|info| format!("{:?}", info).eq_ignore_ascii_case(name))
+            || self
+                .custom_infos
+                .iter()
+                .flatten()
+                .any(|custom| custom.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The template for the value of an info, matching its name case-insensitively
+    pub fn template(&self, name: &str) -> Option<&String> {
+        by_name(&self.templates, name)
+    }
+
+    /// The label shown for an info, either set in the config matching its name
+    /// case-insensitively, translated, or its name
+    pub fn label(&self, entry: &InfoEntry) -> String {
+        let name = entry.name();
+        if let Some(label) = by_name(&self.labels, &name) {
+            return label.clone();
+        }
+        if let InfoEntry::Builtin(info) = entry {
+            let language = self.language.clone().or_else(locale::current_language);
+            if let Some(label) = language.and_then(|lang| locale::translate(*info, &lang)) {
+                return label.to_owned();
+            }
+        }
+        name
+    }
+
+    /// The custom info with this name, ignoring its case like the info lists do
+    pub fn custom_info(&self, name: &str) -> Option<&CustomInfo> {
+        self.custom_infos
+            .as_ref()?
+            .iter()
+            .find(|custom| custom.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// The value for an info in the `labels` or `templates` table, ignoring the case of its name
+fn by_name<'a>(table: &'a Option<HashMap<String, String>>, name: &str) -> Option<&'a String> {
+    table
+        .as_ref()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
 }
 
 pub fn expand_path(path: &str) -> PathBuf {
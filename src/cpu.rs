@@ -0,0 +1,173 @@
+use std::{fs, path::Path};
+
+/// Names of hwmon and thermal zone sensors measuring the CPU temperature
+const SENSORS: &[&str] = &[
+    "coretemp",
+    "k10temp",
+    "zenpower",
+    "cpu_thermal",
+    "cpu-thermal",
+    "x86_pkg_temp",
+    "soc_thermal",
+];
+
+/// Parts of CPU brand strings that do not help telling CPUs apart
+const BRAND_NOISE: &[&str] = &["(R)", "(r)", "(TM)", "(tm)", " with Radeon Graphics"];
+
+/// Words of CPU brand strings that do not help telling CPUs apart
+const BRAND_NOISE_WORDS: &[&str] = &["CPU", "Processor", "Core"];
+
+/// Shortens a CPU brand like `11th Gen Intel(R) Core(TM) i7-1185G7 @ 3.00GHz` to
+/// `Intel i7-1185G7`
+pub fn short_name(brand: &str) -> String {
+    let mut name = match brand.split_once(" @ ") {
+        Some((name, _)) => name.to_string(),
+        None => brand.to_string(),
+    };
+    for noise in BRAND_NOISE {
+        name = name.replace(noise, "");
+    }
+    // core counts like `8-Core` are shown separately, so they are removed like the noise words
+    let words: Vec<&str> = name
+        .split_whitespace()
+        .filter(|word| !word.ends_with("-Core") && !BRAND_NOISE_WORDS.contains(word))
+        .collect();
+    // generations like `11th Gen` come before the vendor
+    let start = words
+        .iter()
+        .position(|word| *word == "Gen")
+        .map_or(0, |index| index + 1);
+    words[start..].join(" ")
+}
+
+/// The maximum frequency of the first CPU in MHz, read from `sys/devices/system/cpu` relative to
+/// the root directory
+pub fn max_frequency(root: &Path) -> Option<u64> {
+    let khz = fs::read_to_string(root.join("sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq"))
+        .ok()?;
+    Some(khz.trim().parse::<u64>().ok()? / 1000)
+}
+
+/// The CPU temperature in °C, read from `sys/class/hwmon` or `sys/class/thermal` relative to the
+/// root directory
+pub fn temperature(root: &Path) -> Option<f64> {
+    hwmon_temperature(root).or_else(|| thermal_zone_temperature(root))
+}
+
+fn hwmon_temperature(root: &Path) -> Option<f64> {
+    let mut dirs: Vec<_> = fs::read_dir(root.join("sys/class/hwmon"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().find_map(|dir| {
+        let name = fs::read_to_string(dir.join("name")).ok()?;
+        if !SENSORS.contains(&name.trim()) {
+            return None;
+        }
+        // prefer the package or control temperature over the ones of single cores
+        let input = (1..=32)
+            .find(|index| {
+                fs::read_to_string(dir.join(format!("temp{index}_label"))).is_ok_and(|label| {
+                    let label = label.trim();
+                    label.starts_with("Package") || label == "Tctl" || label == "Tdie"
+                })
+            })
+            .unwrap_or(1);
+        read_millidegrees(&dir.join(format!("temp{input}_input")))
+    })
+}
+
+fn thermal_zone_temperature(root: &Path) -> Option<f64> {
+    let mut dirs: Vec<_> = fs::read_dir(root.join("sys/class/thermal"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("thermal_zone"))
+        })
+        .collect();
+    dirs.sort();
+    dirs.into_iter().find_map(|dir| {
+        let kind = fs::read_to_string(dir.join("type")).ok()?;
+        if !SENSORS.contains(&kind.trim()) {
+            return None;
+        }
+        read_millidegrees(&dir.join("temp"))
+    })
+}
+
+fn read_millidegrees(path: &Path) -> Option<f64> {
+    let value = fs::read_to_string(path).ok()?;
+    Some(value.trim().parse::<f64>().ok()? / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn short_names() {
+        let cases = [
+            (
+                "11th Gen Intel(R) Core(TM) i7-1185G7 @ 3.00GHz",
+                "Intel i7-1185G7",
+            ),
+            (
+                "AMD Ryzen 7 5800H with Radeon Graphics",
+                "AMD Ryzen 7 5800H",
+            ),
+            ("AMD Ryzen 9 5950X 16-Core Processor", "AMD Ryzen 9 5950X"),
+            (
+                "Intel(R) Xeon(R) CPU E5-2680 v4 @ 2.40GHz",
+                "Intel Xeon E5-2680 v4",
+            ),
+            ("Apple M2", "Apple M2"),
+        ];
+        for (brand, name) in cases {
+            assert_eq!(short_name(brand), name);
+        }
+    }
+
+    #[test]
+    fn max_frequency_in_mhz() {
+        let root = FakeRoot::new("cpu-frequency");
+        assert_eq!(max_frequency(root.path()), None);
+        root.file(
+            "sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq",
+            "4800000\n",
+        );
+        assert_eq!(max_frequency(root.path()), Some(4800));
+    }
+
+    #[test]
+    fn hwmon_package_temperature() {
+        let root = FakeRoot::new("cpu-hwmon");
+        root.file("sys/class/hwmon/hwmon0/name", "acpitz\n")
+            .file("sys/class/hwmon/hwmon0/temp1_input", "27800\n")
+            .file("sys/class/hwmon/hwmon1/name", "coretemp\n")
+            .file("sys/class/hwmon/hwmon1/temp1_label", "Core 0\n")
+            .file("sys/class/hwmon/hwmon1/temp1_input", "51000\n")
+            .file("sys/class/hwmon/hwmon1/temp2_label", "Package id 0\n")
+            .file("sys/class/hwmon/hwmon1/temp2_input", "54500\n")
+            .file("sys/class/thermal/thermal_zone0/type", "x86_pkg_temp\n")
+            .file("sys/class/thermal/thermal_zone0/temp", "60000\n");
+        assert_eq!(temperature(root.path()), Some(54.5));
+    }
+
+    #[test]
+    fn thermal_zone_temperature_fallback() {
+        let root = FakeRoot::new("cpu-thermal");
+        assert_eq!(temperature(root.path()), None);
+        root.file("sys/class/hwmon/hwmon0/name", "nvme\n")
+            .file("sys/class/hwmon/hwmon0/temp1_input", "38850\n")
+            .file("sys/class/thermal/thermal_zone0/type", "acpitz\n")
+            .file("sys/class/thermal/thermal_zone0/temp", "25000\n")
+            .file("sys/class/thermal/thermal_zone1/type", "cpu-thermal\n")
+            .file("sys/class/thermal/thermal_zone1/temp", "47236\n");
+        assert_eq!(temperature(root.path()), Some(47.236));
+    }
+}
@@ -0,0 +1,219 @@
+use std::{path::Path, process::Command, time::Instant};
+
+use crate::command;
+
+/// A desktop environment or window manager and how to get its version
+struct Program {
+    name: &'static str,
+    /// The names this program is known by, in `XDG_CURRENT_DESKTOP` or as a process
+    ids: &'static [&'static str],
+    command: &'static str,
+    args: &'static [&'static str],
+}
+
+impl Program {
+    const fn new(name: &'static str, ids: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            ids,
+            command: "",
+            args: &[],
+        }
+    }
+
+    const fn with_version(mut self, command: &'static str, args: &'static [&'static str]) -> Self {
+        self.command = command;
+        self.args = args;
+        self
+    }
+
+    fn version(&self, deadline: Instant) -> Option<String> {
+        if self.command.is_empty() {
+            return None;
+        }
+        parse_version(&command::run(
+            Command::new(self.command).args(self.args),
+            deadline,
+        )?)
+    }
+}
+
+const DESKTOPS: &[Program] = &[
+    Program::new("GNOME", &["GNOME", "GNOME-Classic", "GNOME-Flashback"])
+        .with_version("gnome-shell", &["--version"]),
+    Program::new("Plasma", &["KDE", "plasma"]).with_version("plasmashell", &["--version"]),
+    Program::new("Xfce", &["XFCE", "xfce"]).with_version("xfce4-session", &["--version"]),
+    Program::new("Cinnamon", &["X-Cinnamon", "Cinnamon", "cinnamon"])
+        .with_version("cinnamon", &["--version"]),
+    Program::new("MATE", &["MATE", "mate"]).with_version("mate-session", &["--version"]),
+    Program::new("LXQt", &["LXQt", "lxqt"]).with_version("lxqt-session", &["--version"]),
+    Program::new("LXDE", &["LXDE", "lxde"]),
+    Program::new("Budgie", &["Budgie", "budgie-desktop"])
+        .with_version("budgie-desktop", &["--version"]),
+    Program::new("Pantheon", &["Pantheon", "pantheon"]),
+    Program::new("Deepin", &["Deepin", "deepin", "DDE"]),
+    Program::new("Unity", &["Unity", "unity"]),
+    Program::new("COSMIC", &["COSMIC", "cosmic"]),
+    Program::new("Enlightenment", &["Enlightenment", "enlightenment"]),
+];
+
+const WINDOW_MANAGERS: &[Program] = &[
+    Program::new("KWin", &["kwin_wayland", "kwin_x11", "kwin"])
+        .with_version("kwin_wayland", &["--version"]),
+    Program::new("Mutter", &["gnome-shell", "mutter"]),
+    Program::new("Muffin", &["cinnamon", "muffin"]),
+    Program::new("Xfwm4", &["xfwm4"]).with_version("xfwm4", &["--version"]),
+    Program::new("Marco", &["marco"]).with_version("marco", &["--version"]),
+    Program::new("Openbox", &["openbox"]).with_version("openbox", &["--version"]),
+    Program::new("Sway", &["sway"]).with_version("sway", &["--version"]),
+    Program::new("Hyprland", &["Hyprland"]).with_version("Hyprland", &["--version"]),
+    Program::new("river", &["river"]).with_version("river", &["-version"]),
+    Program::new("Wayfire", &["wayfire"]).with_version("wayfire", &["--version"]),
+    Program::new("labwc", &["labwc"]).with_version("labwc", &["--version"]),
+    Program::new("niri", &["niri"]).with_version("niri", &["--version"]),
+    Program::new("Weston", &["weston"]).with_version("weston", &["--version"]),
+    Program::new("i3", &["i3"]).with_version("i3", &["--version"]),
+    Program::new("bspwm", &["bspwm"]).with_version("bspwm", &["-v"]),
+    Program::new("awesome", &["awesome"]).with_version("awesome", &["--version"]),
+    Program::new("dwm", &["dwm"]).with_version("dwm", &["-v"]),
+    Program::new("herbstluftwm", &["herbstluftwm"]).with_version("herbstluftwm", &["--version"]),
+    Program::new("Qtile", &["qtile"]).with_version("qtile", &["--version"]),
+    Program::new("xmonad", &["xmonad"]).with_version("xmonad", &["--version"]),
+    Program::new("Fluxbox", &["fluxbox"]).with_version("fluxbox", &["-version"]),
+    Program::new("IceWM", &["icewm"]).with_version("icewm", &["--version"]),
+    Program::new("FVWM", &["fvwm", "fvwm3"]),
+    Program::new("spectrwm", &["spectrwm"]),
+    Program::new("LeftWM", &["leftwm"]).with_version("leftwm", &["--version"]),
+    Program::new("Compiz", &["compiz"]).with_version("compiz", &["--version"]),
+    Program::new("Metacity", &["metacity"]).with_version("metacity", &["--version"]),
+    Program::new("Enlightenment", &["enlightenment"]),
+];
+
+/// Finds the desktop environment in the values of `XDG_CURRENT_DESKTOP` or `DESKTOP_SESSION`,
+/// returning its name and version
+///
+/// `XDG_CURRENT_DESKTOP` may list multiple names like `ubuntu:GNOME`, where the first known one
+/// is used. Unknown desktops are shown as they are.
+pub fn desktop(
+    current_desktop: Option<&str>,
+    session: Option<&str>,
+    deadline: Instant,
+) -> Option<(String, Option<String>)> {
+    let ids: Vec<&str> = match (current_desktop, session) {
+        (Some(desktop), _) if !desktop.is_empty() => desktop.split(':').collect(),
+        // sessions may be given as the path of their `.desktop` file
+        (_, Some(session)) if !session.is_empty() => vec![Path::new(session)
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or(session)],
+        _ => return None,
+    };
+    match ids
+        .iter()
+        .find_map(|id| DESKTOPS.iter().find(|desktop| desktop.ids.contains(id)))
+    {
+        Some(desktop) => Some((desktop.name.to_string(), desktop.version(deadline))),
+        None => Some((ids.first()?.to_string(), None)),
+    }
+}
+
+/// Finds the window manager among the names of the running processes, returning its name and
+/// version
+pub fn window_manager<'a>(
+    mut process_names: impl Iterator<Item = &'a str>,
+    deadline: Instant,
+) -> Option<(&'static str, Option<String>)> {
+    let window_manager = process_names.find_map(|process| {
+        WINDOW_MANAGERS.iter().find(|wm| {
+            // compiled xmonad configs are called like `xmonad-x86_64-linux`
+            wm.ids.contains(&process) || (wm.name == "xmonad" && process.starts_with("xmonad-"))
+        })
+    })?;
+    Some((window_manager.name, window_manager.version(deadline)))
+}
+
+/// The display server protocol of the session from the values of `XDG_SESSION_TYPE`,
+/// `WAYLAND_DISPLAY` and `DISPLAY`
+pub fn session_type(
+    session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    display: Option<&str>,
+) -> Option<&'static str> {
+    match session_type {
+        Some("wayland") => return Some("Wayland"),
+        Some("x11") => return Some("X11"),
+        Some("tty") => return Some("TTY"),
+        _ => {}
+    }
+    if wayland_display.is_some_and(|display| !display.is_empty()) {
+        Some("Wayland")
+    } else if display.is_some_and(|display| !display.is_empty()) {
+        Some("X11")
+    } else {
+        None
+    }
+}
+
+/// The first word of the first line that looks like a version, like `42.5` in
+/// `GNOME Shell 42.5`
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v').trim_end_matches([',', ')']))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn desktop_names() {
+        let name = |current, session| {
+            desktop(current, session, Instant::now() + Duration::from_secs(5)).map(|(name, _)| name)
+        };
+        assert_eq!(name(Some("ubuntu:GNOME"), None).as_deref(), Some("GNOME"));
+        assert_eq!(name(Some("KDE"), Some("plasma")).as_deref(), Some("Plasma"));
+        assert_eq!(name(Some("foo:bar"), None).as_deref(), Some("foo"));
+        assert_eq!(
+            name(Some(""), Some("/usr/share/xsessions/xfce.desktop")).as_deref(),
+            Some("Xfce")
+        );
+        assert_eq!(name(None, Some("mysession")).as_deref(), Some("mysession"));
+        assert_eq!(name(None, None), None);
+        assert_eq!(name(Some(""), Some("")), None);
+    }
+
+    #[test]
+    fn session_types() {
+        assert_eq!(
+            session_type(Some("x11"), Some("wayland-0"), None),
+            Some("X11")
+        );
+        assert_eq!(session_type(Some("tty"), None, Some(":0")), Some("TTY"));
+        assert_eq!(
+            session_type(Some("unspecified"), Some("wayland-0"), Some(":0")),
+            Some("Wayland")
+        );
+        assert_eq!(session_type(None, Some(""), Some(":0")), Some("X11"));
+        assert_eq!(session_type(None, None, Some("")), None);
+    }
+
+    #[test]
+    fn versions() {
+        assert_eq!(parse_version("GNOME Shell 42.5\n").as_deref(), Some("42.5"));
+        assert_eq!(parse_version("sway version 1.9\n").as_deref(), Some("1.9"));
+        assert_eq!(
+            parse_version("i3 version 4.23 (2023-10-29) © 2009 Michael Stapelberg\n").as_deref(),
+            Some("4.23")
+        );
+        assert_eq!(parse_version("river v0.3.0,\n").as_deref(), Some("0.3.0"));
+        assert_eq!(parse_version("no version here\n1.0\n"), None);
+        assert_eq!(parse_version(""), None);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{fs, path::Path};
+
+/// Locations of the PCI ID database relative to the root directory
+const PCI_IDS: &[&str] = &[
+    "usr/share/hwdata/pci.ids",
+    "usr/share/misc/pci.ids",
+    "usr/share/pci.ids",
+];
+
+/// Short names of common GPU vendors, used instead of the long names from the database
+const VENDORS: &[(u16, &str)] = &[
+    (0x1002, "AMD"),
+    (0x10de, "NVIDIA"),
+    (0x8086, "Intel"),
+    (0x1af4, "Red Hat"),
+    (0x1234, "QEMU"),
+    (0x15ad, "VMware"),
+    (0x80ee, "VirtualBox"),
+];
+
+/// A display controller on the PCI bus
+pub struct Gpu {
+    pub slot: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub vendor: Option<String>,
+    pub device: Option<String>,
+    pub driver: Option<String>,
+}
+
+impl Gpu {
+    /// The vendor and device name, falling back to the hexadecimal IDs when they are unknown
+    pub fn name(&self) -> String {
+        let vendor = match &self.vendor {
+            Some(vendor) => vendor.clone(),
+            None => format!("Vendor {:04x}", self.vendor_id),
+        };
+        match &self.device {
+            Some(device) => format!("{vendor} {device}"),
+            None => format!("{vendor} Device {:04x}", self.device_id),
+        }
+    }
+}
+
+/// Finds all display controllers in `sys/bus/pci/devices` relative to the root directory
+///
+/// Names are resolved with the first PCI ID database found.
+pub fn detect(root: &Path) -> Vec<Gpu> {
+    let entries = match fs::read_dir(root.join("sys/bus/pci/devices")) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut gpus: Vec<Gpu> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            // class 0x03 is a display controller, regardless of its subclass
+            if !read_hex(&path.join("class"))?.starts_with("03") {
+                return None;
+            }
+            Some(Gpu {
+                slot: entry.file_name().to_string_lossy().into_owned(),
+                vendor_id: u16::from_str_radix(&read_hex(&path.join("vendor"))?, 16).ok()?,
+                device_id: u16::from_str_radix(&read_hex(&path.join("device"))?, 16).ok()?,
+                vendor: None,
+                device: None,
+                driver: fs::read_link(path.join("driver"))
+                    .ok()
+                    .and_then(|driver| Some(driver.file_name()?.to_string_lossy().into_owned())),
+            })
+        })
+        .collect();
+    gpus.sort_by(|a, b| a.slot.cmp(&b.slot));
+
+    if gpus.is_empty() {
+        return gpus;
+    }
+    let database = PCI_IDS
+        .iter()
+        .find_map(|path| fs::read_to_string(root.join(path)).ok());
+    for gpu in &mut gpus {
+        let (vendor, device) = match &database {
+            Some(database) => lookup(database, gpu.vendor_id, gpu.device_id),
+            None => (None, None),
+        };
+        gpu.vendor = VENDORS
+            .iter()
+            .find(|(id, _)| *id == gpu.vendor_id)
+            .map(|(_, name)| name.to_string())
+            .or(vendor);
+        gpu.device = device;
+    }
+    gpus
+}
+
+/// Reads a sysfs value like `0x030000` without its `0x` prefix
+fn read_hex(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    Some(value.trim().trim_start_matches("0x").to_lowercase())
+}
+
+/// Looks up the vendor and device names in the contents of a `pci.ids` file
+///
+/// Vendors are listed unindented with their devices indented by one tab below them, for example
+/// `8086  Intel Corporation` followed by `\t9a49  TigerLake-LP GT2 [Iris Xe Graphics]`.
+fn lookup(database: &str, vendor_id: u16, device_id: u16) -> (Option<String>, Option<String>) {
+    let vendor_id = format!("{vendor_id:04x}");
+    let device_id = format!("{device_id:04x}");
+    let mut vendor = None;
+    for line in database.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if let Some(device) = line.strip_prefix('\t') {
+            if vendor.is_none() || device.starts_with('\t') {
+                continue;
+            }
+            if let Some((id, name)) = device.split_once("  ") {
+                if id == device_id {
+                    return (vendor, Some(name.trim().to_string()));
+                }
+            }
+        } else if vendor.is_some() {
+            // the devices of the vendor are over
+            break;
+        } else if let Some((id, name)) = line.split_once("  ") {
+            if id == vendor_id {
+                vendor = Some(name.trim().to_string());
+            }
+        }
+    }
+    (vendor, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    const DATABASE: &str = "\
+# comment
+1b36  Red Hat, Inc.
+\t0100  QXL paravirtual graphic card
+8086  Intel Corporation
+\t9a49  TigerLake-LP GT2 [Iris Xe Graphics]
+\t\t1028 0a38  Latitude 7420
+10de  NVIDIA Corporation
+\t2520  GA106M [GeForce RTX 3060 Mobile / Max-Q]
+";
+
+    fn device(root: &FakeRoot, slot: &str, class: &str, vendor: &str, device: &str) {
+        let dir = format!("sys/bus/pci/devices/{slot}");
+        root.file(&format!("{dir}/class"), format!("{class}\n"))
+            .file(&format!("{dir}/vendor"), format!("{vendor}\n"))
+            .file(&format!("{dir}/device"), format!("{device}\n"));
+    }
+
+    #[test]
+    fn display_controllers() {
+        let root = FakeRoot::new("gpu-detect");
+        device(&root, "0000:01:00.0", "0x030200", "0x10de", "0x2520");
+        device(&root, "0000:00:02.0", "0x030000", "0x8086", "0x9a49");
+        device(&root, "0000:00:14.3", "0x028000", "0x8086", "0xa0f0");
+        device(&root, "0000:00:03.0", "0x030000", "0x1b36", "0x0100");
+        device(&root, "0000:00:04.0", "0x038000", "0xabcd", "0x1234");
+        root.file("usr/share/hwdata/pci.ids", DATABASE);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            "../../../bus/pci/drivers/i915",
+            root.path().join("sys/bus/pci/devices/0000:00:02.0/driver"),
+        )
+        .unwrap();
+
+        let gpus = detect(root.path());
+        let names: Vec<_> = gpus.iter().map(Gpu::name).collect();
+        assert_eq!(
+            names,
+            [
+                "Intel TigerLake-LP GT2 [Iris Xe Graphics]",
+                "Red Hat, Inc. QXL paravirtual graphic card",
+                "Vendor abcd Device 1234",
+                "NVIDIA GA106M [GeForce RTX 3060 Mobile / Max-Q]",
+            ]
+        );
+        #[cfg(unix)]
+        assert_eq!(gpus[0].driver.as_deref(), Some("i915"));
+        assert_eq!(gpus[3].driver, None);
+    }
+
+    #[test]
+    fn without_database() {
+        let root = FakeRoot::new("gpu-no-database");
+        device(&root, "0000:00:02.0", "0x030000", "0x8086", "0x9a49");
+        let gpus = detect(root.path());
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name(), "Intel Device 9a49");
+    }
+
+    #[test]
+    fn lookup_stays_within_vendor() {
+        // the subsystem line and the device of another vendor must not match
+        assert_eq!(
+            lookup(DATABASE, 0x8086, 0x2520),
+            (Some("Intel Corporation".to_string()), None)
+        );
+        assert_eq!(lookup(DATABASE, 0x1028, 0x0a38), (None, None));
+    }
+}
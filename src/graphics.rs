@@ -0,0 +1,255 @@
+use std::{
+    env,
+    fmt::Write,
+    io::{self, Cursor, IsTerminal},
+};
+
+use ansipix::FilterType;
+use image::{DynamicImage, ImageOutputFormat, ImageResult, RgbaImage};
+
+use crate::cli::ImageBackend;
+
+/// The assumed size of a terminal cell in pixels, when the terminal does not report it
+const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+/// Size of a kitty graphics protocol payload chunk
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Characters used for ASCII art, from the least to the most dense
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// An image drawn with a terminal graphics protocol
+pub struct Graphic {
+    /// The escape sequence drawing the image at the cursor position
+    pub data: String,
+    pub rows: usize,
+}
+
+/// Replaces `ImageBackend::Auto` with the best backend the current terminal supports
+pub fn resolve_backend(backend: ImageBackend) -> ImageBackend {
+    if backend != ImageBackend::Auto {
+        return backend;
+    }
+    // graphics escape sequences only make sense when written to a terminal, and are not
+    // passed through by multiplexers
+    if !io::stdout().is_terminal() || env::var_os("TMUX").is_some() {
+        return ImageBackend::Ansi;
+    }
+
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if term.contains("kitty")
+        || term.contains("ghostty")
+        || env::var_os("KITTY_WINDOW_ID").is_some()
+        || ["WezTerm", "ghostty"].contains(&term_program.as_str())
+    {
+        ImageBackend::Kitty
+    } else if term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || term.starts_with("yaft")
+        || term.starts_with("contour")
+        || term_program == "iTerm.app"
+    {
+        ImageBackend::Sixel
+    } else {
+        ImageBackend::Ansi
+    }
+}
+
+/// The size of a terminal cell in pixels
+#[cfg(unix)]
+fn cell_size() -> (u32, u32) {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes into the given winsize struct
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result != 0 || size.ws_row == 0 || size.ws_col == 0 {
+        return DEFAULT_CELL_SIZE;
+    }
+    match (size.ws_xpixel / size.ws_col, size.ws_ypixel / size.ws_row) {
+        // some terminals report no or too few pixels
+        (0, _) | (_, 0) => DEFAULT_CELL_SIZE,
+        (width, height) => (width as u32, height as u32),
+    }
+}
+
+#[cfg(not(unix))]
+fn cell_size() -> (u32, u32) {
+    DEFAULT_CELL_SIZE
+}
+
+/// Renders an image `cols` terminal cells wide with the kitty or sixel graphics protocol
+///
+/// The image is scaled to the actual pixel size it will be displayed at.
+/// For `ImageBackend::Kitty` the cursor position is left unchanged after drawing.
+pub fn render_graphic(
+    bytes: &[u8],
+    backend: ImageBackend,
+    cols: u32,
+    alpha_threshold: u8,
+    filter: FilterType,
+) -> ImageResult<Graphic> {
+    let image = image::load_from_memory(bytes)?;
+    let (cell_width, cell_height) = cell_size();
+    let width = cols * cell_width;
+    let height = image.height() * width / image.width().max(1);
+    let image = image.resize(width, height.max(1), filter);
+    let rows = image.height().div_ceil(cell_height) as usize;
+
+    let data = match backend {
+        ImageBackend::Sixel => sixel(&image.to_rgba8(), alpha_threshold),
+        _ => kitty(&image, cols)?,
+    };
+    Ok(Graphic { data, rows })
+}
+
+fn kitty(image: &DynamicImage, cols: u32) -> ImageResult<String> {
+    let mut png = vec![];
+    image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+    let payload = base64::encode(png);
+
+    let mut out = String::new();
+    let chunks: Vec<_> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        // chunks are slices of ASCII base64 output and therefore valid UTF-8
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            // transmit and display a PNG without moving the cursor or sending a response
+            write!(
+                out,
+                "\x1b_Ga=T,f=100,q=2,C=1,c={cols},m={more};{chunk}\x1b\\"
+            )
+            .ok();
+        } else {
+            write!(out, "\x1b_Gm={more};{chunk}\x1b\\").ok();
+        }
+    }
+    Ok(out)
+}
+
+/// Index into a 6x6x6 color cube, or `None` for transparent pixels
+fn sixel_color(pixel: &image::Rgba<u8>, alpha_threshold: u8) -> Option<usize> {
+    if pixel[3] < alpha_threshold {
+        return None;
+    }
+    let level = |c: u8| (c as usize * 5 + 127) / 255;
+    Some(level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]))
+}
+
+fn push_sixel_run(out: &mut String, sixel: char, count: usize) {
+    match count {
+        0 => {}
+        1..=3 => out.extend(std::iter::repeat(sixel).take(count)),
+        _ => {
+            write!(out, "!{count}{sixel}").ok();
+        }
+    }
+}
+
+fn sixel(image: &RgbaImage, alpha_threshold: u8) -> String {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let pixels: Vec<_> = image
+        .pixels()
+        .map(|pixel| sixel_color(pixel, alpha_threshold))
+        .collect();
+
+    // keep the background of transparent pixels and use the actual size as raster attributes
+    let mut out = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+    let mut used = [false; 216];
+    for color in pixels.iter().flatten() {
+        used[*color] = true;
+    }
+    for color in (0..216).filter(|c| used[*c]) {
+        write!(
+            out,
+            "#{color};2;{};{};{}",
+            color / 36 * 20,
+            color / 6 % 6 * 20,
+            color % 6 * 20,
+        )
+        .ok();
+    }
+
+    for band in (0..height).step_by(6) {
+        let band_pixels = &pixels[band * width..((band + 6).min(height) * width)];
+        let mut colors: Vec<_> = band_pixels.iter().flatten().copied().collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (index, color) in colors.into_iter().enumerate() {
+            if index > 0 {
+                // go back to the start of the band to draw the next color
+                out.push('$');
+            }
+            write!(out, "#{color}").ok();
+
+            let mut run = ('?', 0);
+            for x in 0..width {
+                let mut bits = 0;
+                for (row, chunk) in band_pixels.chunks(width).enumerate() {
+                    if chunk[x] == Some(color) {
+                        bits |= 1 << row;
+                    }
+                }
+                let sixel = char::from(63 + bits);
+                if sixel == run.0 {
+                    run.1 += 1;
+                } else {
+                    push_sixel_run(&mut out, run.0, run.1);
+                    run = (sixel, 1);
+                }
+            }
+            push_sixel_run(&mut out, run.0, run.1);
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Renders an image as ASCII art `cols` characters wide, where denser characters are used for
+/// brighter pixels
+///
+/// Transparent pixels are left empty, all others get at least the least dense character.
+pub fn render_ascii(
+    bytes: &[u8],
+    cols: u32,
+    alpha_threshold: u8,
+    filter: FilterType,
+    colors: bool,
+) -> ImageResult<String> {
+    let image = image::load_from_memory(bytes)?;
+    // terminal cells are about twice as high as they are wide
+    let rows = (image.height() * cols / image.width().max(1) / 2).max(1);
+    let image = image.resize_exact(cols, rows, filter).to_rgba8();
+
+    let mut out = String::new();
+    for row in image.rows() {
+        let mut color = None;
+        for pixel in row {
+            if pixel[3] < alpha_threshold {
+                out.push(' ');
+                continue;
+            }
+            let luminance =
+                0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
+            let index = 1 + (luminance / 256.0 * (ASCII_RAMP.len() - 1) as f32) as usize;
+            if colors && color != Some([pixel[0], pixel[1], pixel[2]]) {
+                write!(out, "\x1b[38;2;{};{};{}m", pixel[0], pixel[1], pixel[2]).ok();
+                color = Some([pixel[0], pixel[1], pixel[2]]);
+            }
+            out.push(ASCII_RAMP[index] as char);
+        }
+        if color.is_some() {
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
@@ -0,0 +1,107 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// Sends a `GET` request to a plain `http://` URL and returns the response body when the status
+/// is `200`
+///
+/// The timeout applies to connecting and to each read and write separately. HTTP/1.0 is used so
+/// that servers neither keep the connection open nor use chunked responses.
+pub fn get(url: &str, timeout: Duration) -> Option<String> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    // IPv6 hosts are written like `[::1]:8080`
+    let address = if authority.contains(':') && !authority.ends_with(']') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut stream = address
+        .to_socket_addrs()
+        .ok()?
+        .find_map(|addr| TcpStream::connect_timeout(&addr, timeout).ok())?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    write!(
+        stream,
+        "GET {path} HTTP/1.0\r\nHost: {authority}\r\nUser-Agent: pixfetch/{}\r\nAccept: text/plain\r\n\r\n",
+        env!("CARGO_PKG_VERSION"),
+    )
+    .ok()?;
+
+    let mut response = vec![];
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.lines().next()?.split_whitespace().nth(1)?;
+    (status == "200").then(|| body.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{net::TcpListener, thread, time::Instant};
+
+    use super::*;
+
+    /// Serves one request on a local port with the given raw response, or never answers when it
+    /// is `None`, returning the URL to request
+    pub fn serve(response: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ip", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            match response {
+                Some(response) => stream.write_all(response.as_bytes()).unwrap(),
+                None => thread::sleep(Duration::from_secs(10)),
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn ok_response() {
+        let url = serve(Some(
+            "HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\n203.0.113.7\n",
+        ));
+        assert_eq!(
+            get(&url, Duration::from_secs(5)).as_deref(),
+            Some("203.0.113.7\n")
+        );
+    }
+
+    #[test]
+    fn error_status() {
+        let url = serve(Some("HTTP/1.0 404 Not Found\r\n\r\n203.0.113.7"));
+        assert_eq!(get(&url, Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn no_answer() {
+        let url = serve(None);
+        let start = Instant::now();
+        assert_eq!(get(&url, Duration::from_millis(200)), None);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn only_plain_http() {
+        assert_eq!(
+            get("https://127.0.0.1/ip", Duration::from_millis(200)),
+            None
+        );
+    }
+}
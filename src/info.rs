@@ -1,37 +1,270 @@
-use crate::cli::Info;
-#[cfg(not(target_os = "android"))]
+use crate::{
+    cache,
+    cli::{Config, CustomInfo, Info, InfoEntry, ShellMode, UnitSystem},
+    command,
+    config::expand_path,
+    cpu, desktop, gpu, init, network, packages, power, shell, template, terminal, theme, units,
+    users, virt,
+};
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
 use battery::{units::ratio::percent, State};
 use chrono::Duration;
 #[cfg(target_os = "android")]
 use serde::Deserialize;
-use std::{env, io::Read, process::Command};
+use serde::{Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{mpsc, Arc, OnceLock},
+    thread,
+    time::{self, Instant},
+};
 use sysinfo::{
-    CpuExt, CpuRefreshKind, Pid, ProcessExt, ProcessRefreshKind, RefreshKind, SystemExt,
+    CpuExt, CpuRefreshKind, Pid, PidExt, ProcessExt, ProcessRefreshKind, RefreshKind, SystemExt,
 };
 use systemstat::Platform;
 
+/// A raw value an info was created from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Field {
+    Int(u64),
+    Float(f64),
+    Str(String),
+}
+
+impl From<u64> for Field {
+    fn from(n: u64) -> Self {
+        Self::Int(n)
+    }
+}
+
+impl From<f64> for Field {
+    fn from(n: f64) -> Self {
+        Self::Float(n)
+    }
+}
+
+impl From<String> for Field {
+    fn from(s: String) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl From<&str> for Field {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_owned())
+    }
+}
+
+/// The value of an info as both the human readable text and the raw fields it was created from
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfoValue {
+    pub text: String,
+    pub fields: Vec<(String, Field)>,
+    /// The values of an info with one line per item, like one per disk
+    pub items: Vec<InfoValue>,
+}
+
+impl InfoValue {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            fields: vec![],
+            items: vec![],
+        }
+    }
+
+    /// Creates a value shown with one line per item, or `None` if there are no items
+    pub fn list(items: Vec<InfoValue>) -> Option<Self> {
+        if items.is_empty() {
+            return None;
+        }
+        Some(Self {
+            text: Self::join(&items),
+            fields: vec![],
+            items,
+        })
+    }
+
+    fn join(items: &[InfoValue]) -> String {
+        items
+            .iter()
+            .map(|item| item.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<Field>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl InfoValue {
+    /// Creates the value for the usage of some resource in bytes
+    ///
+    /// The `used` and `total` sizes are added as fields formatted with the configured unit system,
+    /// in bytes and in each of the IEC and SI units, together with the used `percent`.
+    pub fn usage(used: u64, total: u64, config: &Config) -> Self {
+        let system = config.unit_system.unwrap_or(crate::DEFAULT_UNIT_SYSTEM);
+        let mut value = InfoValue::new(units::format_usage(
+            used,
+            total,
+            system,
+            config.show_percent.unwrap_or(crate::DEFAULT_SHOW_PERCENT),
+        ));
+        for (name, bytes) in [("used", used), ("total", total)] {
+            value = value
+                .with(name, units::format_size(bytes, system))
+                .with(format!("{name}_bytes"), bytes);
+            for system in [UnitSystem::Iec, UnitSystem::Si] {
+                for (power, unit) in system.units().iter().enumerate().skip(1).take(4) {
+                    value = value.with(
+                        format!("{name}_{}", unit.to_lowercase()),
+                        bytes as f64 / (system.base() as f64).powi(power as i32),
+                    );
+                }
+            }
+        }
+        value.with("percent", units::percent(used, total))
+    }
+}
+
+impl From<String> for InfoValue {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// Serializes as a map of the raw fields
+pub fn serialize_fields<S: Serializer>(
+    fields: &[(String, Field)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(fields.iter().map(|(name, value)| (name, value)))
+}
+
+impl InfoEntry {
+    pub fn get_info(&self, sys: &System, config: &Config) -> Option<InfoValue> {
+        let mut value = match self {
+            InfoEntry::Builtin(info) => info.get_info(sys, config),
+            InfoEntry::Custom(name) => sys.custom(config.custom_info(name)?),
+        }?;
+        if let Some(template) = config.template(&self.name()) {
+            if value.items.is_empty() {
+                value.text = template::render(template, &value.text, &value.fields);
+            } else {
+                for item in &mut value.items {
+                    item.text = template::render(template, &item.text, &item.fields);
+                }
+                value.text = InfoValue::join(&value.items);
+            }
+        }
+        Some(value)
+    }
+
+    /// Whether this info is shown with a label in front of its value
+    pub fn is_labeled(&self) -> bool {
+        !matches!(
+            self,
+            InfoEntry::Builtin(
+                Info::UserAtHostname | Info::Seperator | Info::Colors1 | Info::Colors2
+            )
+        )
+    }
+
+    /// The name used as the key of this info
+    pub fn name(&self) -> String {
+        match self {
+            InfoEntry::Builtin(info) => format!("{:?}", info),
+            InfoEntry::Custom(name) => name.clone(),
+        }
+    }
+}
+
 impl Info {
-    pub fn get_info(&self, sys: &mut System) -> Option<String> {
+    pub fn get_info(&self, sys: &System, config: &Config) -> Option<InfoValue> {
         match self {
             Info::UserAtHostname => sys.user_at_hostname(),
-            Info::OS => sys.os(),
-            Info::Host => sys.host(),
-            Info::Kernel => sys.kernel(),
+            Info::OS => sys.os_info(),
+            Info::Host => sys.host().map(InfoValue::from),
+            Info::Virtualization => sys.virtualization(),
+            Info::Kernel => sys.kernel().map(InfoValue::from),
             Info::Uptime => sys.uptime(),
+            Info::Init => sys.init(config),
+            Info::Load => sys.load(),
+            Info::Processes => sys.process_count(),
+            Info::Users => sys.users(),
             Info::Packages => sys.packages(),
-            Info::Shell => sys.shell(),
+            Info::Shell => sys.shell(config),
+            Info::DE => sys.de(),
+            Info::WM => sys.wm(),
+            Info::Session => sys.session(),
+            Info::Theme => sys.appearance(theme::THEME),
+            Info::Icons => sys.appearance(theme::ICONS),
+            Info::Cursor => sys.appearance(theme::CURSOR),
+            Info::Font => sys.appearance(theme::FONT),
             Info::Terminal => sys.terminal(),
             Info::CPU => sys.cpu(),
-            Info::Memory => sys.memory(),
-            Info::Swap => sys.swap(),
+            Info::GPU => sys.gpu(),
+            Info::Memory => sys.memory(config),
+            Info::Swap => sys.swap(config),
+            Info::Disk => sys.disk(config),
+            Info::LocalIP => sys.local_ip(config),
+            Info::PublicIP => sys.public_ip(config),
+            Info::Network => sys.network(config),
             Info::Battery => sys.battery(),
-            Info::Seperator => Some(String::new()),
-            Info::Colors1 => Some(sys.colors1()),
-            Info::Colors2 => Some(sys.colors2()),
+            Info::Seperator => Some(InfoValue::new(String::new())),
+            Info::Colors1 => Some(InfoValue::new(sys.colors1())),
+            Info::Colors2 => Some(InfoValue::new(sys.colors2())),
         }
     }
 }
 
+/// Gets the values of all infos concurrently
+///
+/// Infos which are not done at the deadline of the system are replaced by the timeout
+/// placeholder, or left out if none is set. Infos without a value are always left out.
+pub fn collect(
+    entries: Vec<InfoEntry>,
+    sys: Arc<System>,
+    config: Arc<Config>,
+) -> Vec<(InfoEntry, InfoValue)> {
+    let (sender, receiver) = mpsc::channel();
+    for (index, entry) in entries.iter().enumerate() {
+        let sender = sender.clone();
+        let sys = Arc::clone(&sys);
+        let config = Arc::clone(&config);
+        let entry = entry.clone();
+        thread::spawn(move || {
+            sender.send((index, entry.get_info(&sys, &config))).ok();
+        });
+    }
+    drop(sender);
+
+    let mut values = vec![None; entries.len()];
+    while let Ok((index, value)) =
+        receiver.recv_timeout(sys.deadline.saturating_duration_since(Instant::now()))
+    {
+        values[index] = Some(value);
+    }
+    // the commands of infos which did not finish in time would outlive pixfetch otherwise
+    command::kill_running();
+
+    entries
+        .into_iter()
+        .zip(values)
+        .filter_map(|(entry, value)| match value {
+            Some(value) => Some((entry, value?)),
+            None => Some((entry, InfoValue::new(config.timeout_placeholder.clone()?))),
+        })
+        .collect()
+}
+
 #[cfg(target_os = "android")]
 #[derive(Deserialize)]
 #[allow(dead_code)]
@@ -47,32 +280,63 @@ struct BatteryStatus {
 pub struct System {
     sysinfo: sysinfo::System,
     systemstat: systemstat::System,
+    processes: OnceLock<sysinfo::System>,
+    /// The time at which collecting infos is stopped and running commands are killed
+    deadline: Instant,
 }
 
 impl System {
-    pub fn new() -> System {
+    pub fn new(deadline: Instant) -> System {
         System {
             sysinfo: sysinfo::System::new_with_specifics(
                 RefreshKind::new()
-                    .with_cpu(CpuRefreshKind::new())
+                    .with_cpu(CpuRefreshKind::new().with_frequency())
                     .with_memory(),
             ),
             systemstat: systemstat::System::new(),
+            processes: OnceLock::new(),
+            deadline,
         }
     }
 
-    pub fn user_at_hostname(&self) -> Option<String> {
-        let user = env::var("USER");
-        if let Ok(user) = user {
-            return Some(format!("{}@{}", user, self.sysinfo.host_name()?));
-        } else {
-            return Some(format!(
-                "{}@{}",
-                String::from_utf8_lossy(&Command::new("id").arg("-un").output().ok()?.stdout)
-                    .replace('\n', ""),
-                self.sysinfo.host_name()?,
-            ));
-        }
+    /// The process list, only refreshed once the first info needs it
+    fn processes(&self) -> &sysinfo::System {
+        self.processes.get_or_init(|| {
+            let mut processes = sysinfo::System::new();
+            processes.refresh_processes_specifics(ProcessRefreshKind::new());
+            processes
+        })
+    }
+
+    /// The names and parents of all processes by their PID
+    fn process_tree(&self) -> HashMap<u32, terminal::Process<'_>> {
+        self.processes()
+            .processes()
+            .iter()
+            .map(|(pid, process)| {
+                (
+                    pid.as_u32(),
+                    terminal::Process {
+                        name: process.name(),
+                        parent: process.parent().map(|pid| pid.as_u32()),
+                        cmd: process.cmd(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn user_at_hostname(&self) -> Option<InfoValue> {
+        let user = match env::var("USER") {
+            Ok(user) => user,
+            Err(_) => command::run(Command::new("id").arg("-un"), self.deadline)?.replace('\n', ""),
+        };
+        let host_name = self.sysinfo.host_name()?;
+        Some(
+            InfoValue::new(format!("{}@{}", user, host_name))
+                .with("user", user)
+                .with("hostname", host_name),
+        )
     }
 
     pub fn os(&self) -> Option<String> {
@@ -88,6 +352,17 @@ impl System {
         self.sysinfo.name()
     }
 
+    pub fn os_info(&self) -> Option<InfoValue> {
+        let mut value = InfoValue::new(self.os()?);
+        if let Some(name) = self.sysinfo.name() {
+            value = value.with("name", name);
+        }
+        if let Some(version) = self.sysinfo.os_version() {
+            value = value.with("version", version);
+        }
+        Some(value)
+    }
+
     pub fn host(&self) -> Option<String> {
         if cfg!(target_os = "android") {
             return self.sysinfo.name();
@@ -160,191 +435,590 @@ impl System {
         }
         let host_n = host_filtered.join(" ");
         if host_n.is_empty() {
-            return Some(
-                String::from_utf8_lossy(&Command::new("uname").arg("-m").output().ok()?.stdout)
-                    .replace('\n', ""),
-            );
+            Some(command::run(Command::new("uname").arg("-m"), self.deadline)?.replace('\n', ""))
         } else {
             Some(host_n)
         }
     }
 
+    pub fn virtualization(&self) -> Option<InfoValue> {
+        let container = virt::container(Path::new("/"));
+        let hypervisor = virt::hypervisor(Path::new("/"));
+        let text = match (container, hypervisor) {
+            (Some(container), Some(hypervisor)) => format!("{container} on {hypervisor}"),
+            (Some(name), None) | (None, Some(name)) => name.to_string(),
+            (None, None) => return None,
+        };
+        Some(
+            InfoValue::new(text)
+                .with("container", container.unwrap_or_default())
+                .with("hypervisor", hypervisor.unwrap_or_default()),
+        )
+    }
+
     pub fn kernel(&self) -> Option<String> {
         self.sysinfo.kernel_version()
     }
 
-    pub fn uptime(&self) -> Option<String> {
+    pub fn uptime(&self) -> Option<InfoValue> {
         let duration = Duration::from_std(self.systemstat.uptime().ok()?).ok()?;
 
         let days = duration.num_days();
         let hours = duration.num_hours() - 24 * days;
         let minutes = duration.num_minutes() - 60 * hours - 24 * 60 * days;
 
-        Some(format!(
-            "{}{}{}m",
-            if days > 0 {
-                format!("{}d ", days)
-            } else {
-                String::new()
-            },
-            if hours > 0 {
-                format!("{}h ", hours)
-            } else {
-                String::new()
-            },
-            minutes
-        ))
+        Some(
+            InfoValue::new(format!(
+                "{}{}{}m",
+                if days > 0 {
+                    format!("{}d ", days)
+                } else {
+                    String::new()
+                },
+                if hours > 0 {
+                    format!("{}h ", hours)
+                } else {
+                    String::new()
+                },
+                minutes
+            ))
+            .with("seconds", duration.num_seconds() as u64)
+            .with("days", days as u64)
+            .with("hours", hours as u64)
+            .with("minutes", minutes as u64),
+        )
     }
 
-    pub fn packages(&self) -> Option<String> {
-        let commands = [
-            ("pacman", Command::new("pacman").arg("-Qq").output()),
-            (
-                "dpkg",
-                Command::new("dpkg-query")
-                    .args(["-f", r".\n", "-W"])
-                    .output(),
-            ),
-            ("bonsai", Command::new("bonsai").arg("list").output()),
-            ("pkginfo", Command::new("pkginfo").arg("-i").output()),
-            ("rpm", Command::new("rpm").arg("-qa").output()),
-            ("xbps-query", Command::new("xbps-query").arg("-l").output()),
-            ("apk", Command::new("apk").arg("info").output()),
-            (
-                "guix",
-                Command::new("guix")
-                    .args(["package", "--list-installed"])
-                    .output(),
-            ),
-            ("opkg", Command::new("opkg").arg("list-installed").output()),
-            ("kiss", Command::new("kiss").arg("l").output()),
-            ("cpt-list", Command::new("cpt-list").output()),
-            ("pacman-g2", Command::new("pacman-g2").arg("-Q").output()),
-            ("lvu", Command::new("lvu").arg("installed").output()),
-            ("tce-status", Command::new("tce-status").arg("-i").output()),
-            ("pkg_info", Command::new("pkg_info").output()),
-            ("pkgin", Command::new("pkgin").arg("list").output()),
-            ("sorcery", Command::new("gaze").arg("installed").output()),
-            ("alps", Command::new("alps").arg("showinstalled").output()),
-            ("butch", Command::new("butch").arg("list").output()),
-            (
-                "swupd",
-                Command::new("swupd")
-                    .args(["bundle-list", "--quiet"])
-                    .output(),
-            ),
-            ("pisi", Command::new("pisi").arg("li").output()),
-            ("pacstall", Command::new("pacstall").arg("-L").output()),
-        ];
+    pub fn init(&self, config: &Config) -> Option<InfoValue> {
+        let (name, exe) = init::detect(Path::new("/"))?;
+        let version = init::version(Path::new("/"), &name);
+        let show_failed = config
+            .show_failed_units
+            .unwrap_or(crate::DEFAULT_SHOW_FAILED_UNITS);
+        let failed = if show_failed && name == "systemd" {
+            init::failed_units(self.deadline)
+        } else {
+            None
+        };
 
-        for (pkg_manager, output) in commands.iter() {
-            if let Ok(output) = output {
-                if output.status.success() {
-                    return Some(format!(
-                        "{} ({})",
-                        String::from_utf8_lossy(&output.stdout)
-                            .trim_matches('\n')
-                            .split('\n')
-                            .count(),
-                        pkg_manager,
-                    ));
-                }
+        let mut value = versioned(name, version);
+        if let Some(failed) = failed {
+            if failed > 0 {
+                value.text += &format!(" ({failed} failed)");
             }
+            value = value.with("failed_units", failed as u64);
+        }
+        Some(value.with("path", exe.unwrap_or_default()))
+    }
+
+    pub fn load(&self) -> Option<InfoValue> {
+        let load = self.systemstat.load_average().ok()?;
+        Some(
+            InfoValue::new(format!(
+                "{:.2}, {:.2}, {:.2}",
+                load.one, load.five, load.fifteen
+            ))
+            .with("one", load.one as f64)
+            .with("five", load.five as f64)
+            .with("fifteen", load.fifteen as f64),
+        )
+    }
+
+    pub fn process_count(&self) -> Option<InfoValue> {
+        let count = self.processes().processes().len();
+        if count == 0 {
+            return None;
+        }
+        Some(InfoValue::new(count.to_string()).with("count", count as u64))
+    }
+
+    pub fn users(&self) -> Option<InfoValue> {
+        let sessions = users::logged_in(Path::new("/"))?;
+        let mut names = sessions.clone();
+        names.sort();
+        names.dedup();
+        if names.is_empty() {
+            return None;
+        }
+        Some(
+            InfoValue::new(format!("{} ({})", names.len(), names.join(", ")))
+                .with("count", names.len() as u64)
+                .with("sessions", sessions.len() as u64)
+                .with("users", names.join(", ")),
+        )
+    }
+
+    pub fn packages(&self) -> Option<InfoValue> {
+        let counts = packages::count(self.deadline);
+        if counts.is_empty() {
+            return None;
         }
-        None
+
+        let mut value = InfoValue::new(
+            counts
+                .iter()
+                .map(|(manager, count)| format!("{} ({})", count, manager))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+        .with(
+            "total",
+            counts.iter().map(|(_, count)| count).sum::<usize>() as u64,
+        );
+        for (manager, count) in counts {
+            value = value.with(manager, count as u64);
+        }
+        Some(value)
+    }
+
+    pub fn shell(&self, config: &Config) -> Option<InfoValue> {
+        let current = match config.shell.unwrap_or(crate::DEFAULT_SHELL) {
+            ShellMode::Current => {
+                shell::find(&self.process_tree(), std::process::id()).and_then(|pid| {
+                    let process = self.processes().process(Pid::from_u32(pid))?;
+                    Some((process.name().to_owned(), process.exe().to_path_buf()))
+                })
+            }
+            ShellMode::Login => None,
+        };
+        // the login shell is also used when the current one is unknown
+        let (name, path) = match current {
+            Some(shell) => shell,
+            None => {
+                let path = PathBuf::from(env::var("SHELL").ok()?);
+                (path.file_name()?.to_string_lossy().into_owned(), path)
+            }
+        };
+
+        let version = shell::version(&name, &path, self.deadline);
+        let text = match &version {
+            Some(version) => format!("{name} {version}"),
+            None => name.clone(),
+        };
+        Some(
+            InfoValue::new(text)
+                .with("name", name)
+                .with("version", version.unwrap_or_default())
+                .with("path", path.to_string_lossy().into_owned()),
+        )
+    }
+
+    pub fn de(&self) -> Option<InfoValue> {
+        let (name, version) = desktop::desktop(
+            env::var("XDG_CURRENT_DESKTOP").ok().as_deref(),
+            env::var("DESKTOP_SESSION").ok().as_deref(),
+            self.deadline,
+        )?;
+        Some(versioned(name, version))
     }
 
-    pub fn shell(&self) -> Option<String> {
-        Some(env::var("SHELL").ok()?.rsplit_once('/')?.1.to_owned())
+    pub fn wm(&self) -> Option<InfoValue> {
+        let (name, version) = desktop::window_manager(
+            self.processes()
+                .processes()
+                .values()
+                .map(|process| process.name()),
+            self.deadline,
+        )?;
+        Some(versioned(name.to_string(), version))
     }
 
-    pub fn terminal(&mut self) -> Option<String> {
-        self.sysinfo
-            .refresh_processes_specifics(ProcessRefreshKind::new());
+    pub fn session(&self) -> Option<InfoValue> {
+        let session_type = desktop::session_type(
+            env::var("XDG_SESSION_TYPE").ok().as_deref(),
+            env::var("WAYLAND_DISPLAY").ok().as_deref(),
+            env::var("DISPLAY").ok().as_deref(),
+        )?;
+        Some(InfoValue::new(session_type.to_string()).with("type", session_type))
+    }
+
+    /// A theme setting read from the config files of the toolkits, like the GTK or icon theme
+    pub fn appearance(&self, sources: &[theme::Source]) -> Option<InfoValue> {
+        let (home, config_dir) = home_and_config_dir()?;
+        let values = theme::read(sources, &home, &config_dir);
+        if values.is_empty() {
+            return None;
+        }
+        let mut value = InfoValue::new(theme::format(&values));
+        for (toolkit, setting) in values {
+            value = value.with(toolkit.to_lowercase(), setting);
+        }
+        Some(value)
+    }
+
+    pub fn terminal(&self) -> Option<InfoValue> {
         if let Ok(home) = env::var("HOME") {
             if home.contains("termux") {
-                return Some(String::from("termux"));
+                return Some(InfoValue::new(String::from("termux")).with("name", "termux"));
             }
         }
 
-        let process = self.sysinfo.process(Pid::from(std::process::id() as i32))?;
-        let shell = self.sysinfo.process(process.parent()?)?;
-        let terminal = self.sysinfo.process(shell.parent()?)?;
-
-        let mut name = terminal.name();
-        if name == "electron" {
-            if let Some(parent1) = terminal.parent().and_then(|pid| self.sysinfo.process(pid)) {
-                if parent1.name().contains("code") {
-                    name = "vscode";
-                } else if let Some(parent2) =
-                    parent1.parent().and_then(|pid| self.sysinfo.process(pid))
-                {
-                    if parent2.name().contains("code") {
-                        name = "vscode";
-                    }
-                }
+        let processes = self.process_tree();
+        // the multiplexer servers are not children of the terminal, but the clients attached to
+        // them are
+        let client = |multiplexer: &str| match multiplexer {
+            "tmux" => {
+                env::var_os("TMUX")?;
+                command::run(
+                    Command::new("tmux").args(["display-message", "-p", "#{client_pid}"]),
+                    self.deadline,
+                )?
+                .trim()
+                .parse()
+                .ok()
             }
+            "screen" => terminal::client(&processes, "screen", &env::var("STY").ok()?),
+            "zellij" => {
+                terminal::client(&processes, "zellij", &env::var("ZELLIJ_SESSION_NAME").ok()?)
+            }
+            _ => None,
+        };
+        let name = terminal::find(&processes, std::process::id(), client)?;
+
+        let version = terminal::version(name, self.deadline);
+        let font = home_and_config_dir()
+            .and_then(|(home, config_dir)| terminal::font(name, &home, &config_dir));
+        let mut text = match &version {
+            Some(version) => format!("{name} {version}"),
+            None => name.to_string(),
+        };
+        if let Some(font) = &font {
+            text += &format!(" ({font})");
         }
+        Some(
+            InfoValue::new(text)
+                .with("name", name)
+                .with("version", version.unwrap_or_default())
+                .with("font", font.unwrap_or_default()),
+        )
+    }
 
-        Some(name.to_owned())
+    pub fn cpu(&self) -> Option<InfoValue> {
+        let cpus = self.sysinfo.cpus();
+        let brand = cpus.first()?.brand();
+        let name = cpu::short_name(brand);
+        let frequency = cpus.first()?.frequency();
+        let max_frequency = cpu::max_frequency(Path::new("/"));
+        let temperature = cpu::temperature(Path::new("/"));
+
+        let mut text = format!("{name} ({})", cpus.len());
+        // the maximum frequency is more telling than the current one of an idle CPU
+        if let Some(mhz) = max_frequency.or((frequency > 0).then_some(frequency)) {
+            text += &format!(" @ {:.1}GHz", mhz as f64 / 1000.0);
+        }
+        if let Some(temperature) = temperature {
+            text += &format!(", {temperature:.0}°C");
+        }
+
+        let mut value = InfoValue::new(text)
+            .with("brand", brand)
+            .with("name", name)
+            .with("threads", cpus.len() as u64)
+            .with("frequency_mhz", frequency);
+        if let Some(cores) = self.sysinfo.physical_core_count() {
+            value = value.with("cores", cores as u64);
+        }
+        if let Some(max_frequency) = max_frequency {
+            value = value.with("max_frequency_mhz", max_frequency);
+        }
+        if let Some(temperature) = temperature {
+            value = value.with("temperature", temperature);
+        }
+        Some(value)
     }
 
-    pub fn cpu(&self) -> Option<String> {
-        Some(self.sysinfo.cpus().iter().next()?.brand().to_string())
+    pub fn gpu(&self) -> Option<InfoValue> {
+        InfoValue::list(
+            gpu::detect(Path::new("/"))
+                .into_iter()
+                .map(|gpu| {
+                    InfoValue::new(gpu.name())
+                        .with("vendor", gpu.vendor.unwrap_or_default())
+                        .with("device", gpu.device.unwrap_or_default())
+                        .with("vendor_id", format!("{:04x}", gpu.vendor_id))
+                        .with("device_id", format!("{:04x}", gpu.device_id))
+                        .with("slot", gpu.slot)
+                        .with("driver", gpu.driver.unwrap_or_default())
+                })
+                .collect(),
+        )
     }
 
-    pub fn memory(&self) -> Option<String> {
-        Some(format!(
-            "{:.2}GB / {:.2}GB",
-            (self.sysinfo.used_memory() as f32) / 1024.0 / 1024.0,
-            (self.sysinfo.total_memory() as f32) / 1024.0 / 1024.0,
+    pub fn memory(&self, config: &Config) -> Option<InfoValue> {
+        // sysinfo reports kilobytes
+        Some(InfoValue::usage(
+            self.sysinfo.used_memory() * 1000,
+            self.sysinfo.total_memory() * 1000,
+            config,
         ))
     }
 
-    pub fn swap(&self) -> Option<String> {
+    pub fn swap(&self, config: &Config) -> Option<InfoValue> {
         let total_swap = self.sysinfo.total_swap();
         if total_swap == 0 {
             return None;
         }
-        Some(format!(
-            "{:.2}GB / {:.2}GB",
-            (self.sysinfo.used_swap() as f32) / 1024.0 / 1024.0,
-            (self.sysinfo.total_swap() as f32) / 1024.0 / 1024.0,
+        Some(InfoValue::usage(
+            self.sysinfo.used_swap() * 1000,
+            total_swap * 1000,
+            config,
         ))
     }
 
-    #[cfg(not(target_os = "android"))]
-    pub fn battery(&self) -> Option<String> {
+    pub fn disk(&self, config: &Config) -> Option<InfoValue> {
+        let mounts = match &config.disk_mounts {
+            Some(mounts) => mounts.iter().map(String::as_str).collect(),
+            None => crate::DEFAULT_DISK_MOUNTS.to_vec(),
+        };
+        InfoValue::list(
+            mounts
+                .into_iter()
+                .filter_map(|mount| self.systemstat.mount_at(mount).ok())
+                .filter(|fs| fs.total.as_u64() > 0)
+                .map(|fs| {
+                    let value = InfoValue::usage(
+                        fs.total.as_u64() - fs.free.as_u64(),
+                        fs.total.as_u64(),
+                        config,
+                    );
+                    InfoValue {
+                        text: format!("{} ({}): {}", fs.fs_mounted_on, fs.fs_type, value.text),
+                        ..value
+                    }
+                    .with("mount", fs.fs_mounted_on)
+                    .with("fs_type", fs.fs_type)
+                    .with("device", fs.fs_mounted_from)
+                })
+                .collect(),
+        )
+    }
+
+    /// The addresses of all network interfaces that are not hidden, sorted by the interface name
+    fn interfaces(&self, config: &Config) -> Vec<(String, Vec<network::Address>)> {
+        let hidden = match &config.hidden_interfaces {
+            Some(hidden) => hidden.iter().map(String::as_str).collect(),
+            None => crate::DEFAULT_HIDDEN_INTERFACES.to_vec(),
+        };
+        let networks = match self.systemstat.networks() {
+            Ok(networks) => networks,
+            Err(_) => return vec![],
+        };
+        networks
+            .into_values()
+            .filter(|network| !network::is_hidden(&network.name, &hidden))
+            .map(|network| {
+                let addrs = network
+                    .addrs
+                    .iter()
+                    .filter_map(network::Address::from_systemstat)
+                    .collect();
+                (network.name, addrs)
+            })
+            .collect()
+    }
+
+    pub fn local_ip(&self, config: &Config) -> Option<InfoValue> {
+        InfoValue::list(
+            self.interfaces(config)
+                .into_iter()
+                .filter_map(|(interface, addrs)| {
+                    let mut addrs = addrs.iter().filter(|addr| !addr.is_link_local());
+                    let ipv4 = addrs.clone().find(|addr| addr.addr.is_ipv4());
+                    let ipv6 = addrs.find(|addr| addr.addr.is_ipv6());
+                    let text = match (&ipv4, &ipv6) {
+                        (Some(ipv4), Some(ipv6)) => format!("{ipv4}, {ipv6}"),
+                        (Some(addr), None) | (None, Some(addr)) => addr.to_string(),
+                        (None, None) => return None,
+                    };
+                    Some(
+                        InfoValue::new(format!("{text} ({interface})"))
+                            .with("interface", interface)
+                            .with(
+                                "ipv4",
+                                ipv4.map(|addr| addr.to_string()).unwrap_or_default(),
+                            )
+                            .with(
+                                "ipv6",
+                                ipv6.map(|addr| addr.to_string()).unwrap_or_default(),
+                            ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn public_ip(&self, config: &Config) -> Option<InfoValue> {
+        let url = config.public_ip_url.as_ref()?;
+        let ttl = config.public_ip_ttl.unwrap_or(crate::DEFAULT_PUBLIC_IP_TTL);
+        let ip = network::public_ip(
+            url,
+            ttl,
+            time::Duration::from_millis(crate::DEFAULT_PUBLIC_IP_TIMEOUT),
+            cache::cache_dir().as_deref(),
+        )?;
+        Some(InfoValue::new(ip.clone()).with("ip", ip))
+    }
+
+    pub fn network(&self, config: &Config) -> Option<InfoValue> {
+        InfoValue::list(
+            self.interfaces(config)
+                .into_iter()
+                .map(|(interface, addrs)| {
+                    let (state, mac, speed) = network::link(Path::new("/"), &interface);
+                    let details: Vec<String> = [
+                        state.clone(),
+                        speed.map(|speed| format!("{speed} Mb/s")),
+                        mac.clone(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                    let text = if details.is_empty() {
+                        interface.clone()
+                    } else {
+                        format!("{interface} ({})", details.join(", "))
+                    };
+                    let mut value = InfoValue::new(text)
+                        .with("interface", interface)
+                        .with("state", state.unwrap_or_default())
+                        .with("mac", mac.unwrap_or_default())
+                        .with(
+                            "addresses",
+                            addrs
+                                .iter()
+                                .map(|addr| addr.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        );
+                    if let Some(speed) = speed {
+                        value = value.with("speed_mbps", speed);
+                    }
+                    value
+                })
+                .collect(),
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn battery(&self) -> Option<InfoValue> {
+        let (batteries, ac_online) = power::read(Path::new("/"));
+        let show_names = batteries.len() > 1;
+        let mut items: Vec<InfoValue> = batteries
+            .into_iter()
+            .map(|battery| {
+                let mut details: Vec<String> = battery
+                    .status
+                    .iter()
+                    .filter(|status| *status != "Unknown")
+                    .map(|status| status.to_lowercase())
+                    .collect();
+                if let Some(power) = battery.power {
+                    details.push(format!("{power:.1} W"));
+                }
+                if let Some(minutes) = battery.time_remaining {
+                    let remaining = format!("{}h {}m", minutes / 60, minutes % 60);
+                    details.push(match battery.status.as_deref() {
+                        Some("Charging") => format!("{remaining} until full"),
+                        _ => format!("{remaining} left"),
+                    });
+                }
+
+                let name = match (&battery.model, battery.peripheral) {
+                    (Some(model), true) => model.clone(),
+                    _ => battery.name.clone(),
+                };
+                let mut text = format!("{:.0}%", battery.percentage);
+                if !details.is_empty() {
+                    text += &format!(" ({})", details.join(", "));
+                }
+                if show_names {
+                    text = format!("{name}: {text}");
+                }
+
+                let mut value = InfoValue::new(text)
+                    .with("name", name)
+                    .with("percentage", battery.percentage)
+                    .with("state", battery.status.unwrap_or_default().to_lowercase());
+                if let Some(power) = battery.power {
+                    value = value.with("power_w", power);
+                }
+                if let Some(minutes) = battery.time_remaining {
+                    value = value.with("time_remaining_minutes", minutes);
+                }
+                value
+            })
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+        if let Some(online) = ac_online {
+            let state = if online { "connected" } else { "disconnected" };
+            items.push(
+                InfoValue::new(format!("AC {state}"))
+                    .with("name", "AC")
+                    .with("state", state),
+            );
+        }
+        InfoValue::list(items)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    pub fn battery(&self) -> Option<InfoValue> {
         let manager = battery::Manager::new().ok()?;
         let battery = manager.batteries().ok()?.next()?.ok()?;
-        Some(format!(
-            "{:.0}%{}",
-            battery.state_of_charge().get::<percent>(),
-            match battery.state() {
-                State::Charging => ", charging",
-                State::Discharging => ", discharging",
-                _ => "",
-            }
-        ))
+        let percentage = battery.state_of_charge().get::<percent>();
+        Some(
+            InfoValue::new(format!(
+                "{:.0}%{}",
+                percentage,
+                match battery.state() {
+                    State::Charging => ", charging",
+                    State::Discharging => ", discharging",
+                    _ => "",
+                }
+            ))
+            .with("percentage", percentage as f64)
+            .with("state", battery.state().to_string()),
+        )
     }
 
     #[cfg(target_os = "android")]
-    pub fn battery(&self) -> Option<String> {
-        let status = match Command::new("termux-battery-status").output() {
-            Ok(output) => output.stdout,
-            Err(_) => return None,
+    pub fn battery(&self) -> Option<InfoValue> {
+        let status = command::run(&mut Command::new("termux-battery-status"), self.deadline)?;
+        let status: BatteryStatus = serde_json::from_str(&status).ok()?;
+        Some(
+            InfoValue::new(format!(
+                "{}%{}",
+                status.percentage,
+                match status.status.as_str() {
+                    "CHARGING" => ", charging",
+                    "DISCHARGING" => ", discharging",
+                    _ => "",
+                }
+            ))
+            .with("percentage", status.percentage as f64)
+            .with("state", status.status.to_lowercase()),
+        )
+    }
+
+    pub fn custom(&self, custom: &CustomInfo) -> Option<InfoValue> {
+        let value = if let Some(command) = &custom.command {
+            let timeout = time::Duration::from_millis(
+                custom.timeout.unwrap_or(crate::DEFAULT_CUSTOM_TIMEOUT),
+            );
+            command::run(
+                Command::new("sh").args(["-c", command]),
+                self.deadline.min(Instant::now() + timeout),
+            )?
+        } else {
+            fs::read_to_string(expand_path(custom.file.as_ref()?)).ok()?
         };
-        let status: BatteryStatus =
-            serde_json::from_str(&String::from_utf8_lossy(&status[..])).ok()?;
-        Some(format!(
-            "{}%{}",
-            status.percentage,
-            match status.status.as_str() {
-                "CHARGING" => ", charging",
-                "DISCHARGING" => ", discharging",
-                _ => "",
-            }
-        ))
+
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        Some(InfoValue::new(value.to_owned()))
     }
 
     pub fn colors1(&self) -> String {
@@ -361,3 +1035,24 @@ impl System {
             + "\x1b[0m"
     }
 }
+
+/// The home directory and the user config directory, which is `XDG_CONFIG_HOME` or `~/.config`
+fn home_and_config_dir() -> Option<(PathBuf, PathBuf)> {
+    let home = PathBuf::from(env::var("HOME").ok()?);
+    let config_dir = match env::var("XDG_CONFIG_HOME") {
+        Ok(config_dir) if !config_dir.is_empty() => PathBuf::from(config_dir),
+        _ => home.join(".config"),
+    };
+    Some((home, config_dir))
+}
+
+/// A value of a program name with an optional version, like `GNOME 42.5`
+fn versioned(name: String, version: Option<String>) -> InfoValue {
+    let text = match &version {
+        Some(version) => format!("{name} {version}"),
+        None => name.clone(),
+    };
+    InfoValue::new(text)
+        .with("name", name)
+        .with("version", version.unwrap_or_default())
+}
@@ -0,0 +1,139 @@
+use std::{fs, path::Path, process::Command, time::Instant};
+
+use crate::command;
+
+/// Init systems by the process name or executable name of PID 1
+const INITS: &[(&str, &str)] = &[
+    ("systemd", "systemd"),
+    ("openrc-init", "OpenRC"),
+    ("runit", "runit"),
+    ("runit-init", "runit"),
+    ("s6-svscan", "s6"),
+    ("s6-linux-init", "s6"),
+    ("dinit", "dinit"),
+    ("shepherd", "GNU Shepherd"),
+    ("launchd", "launchd"),
+    ("tini", "tini"),
+    ("dumb-init", "dumb-init"),
+];
+
+/// The init system running as PID 1 and the path of its executable, relative to the root
+/// directory
+///
+/// Unknown init systems are shown by their process name. A plain `init` is reported as OpenRC
+/// when OpenRC has created its runtime directory, and as SysVinit otherwise.
+pub fn detect(root: &Path) -> Option<(String, Option<String>)> {
+    let comm = fs::read_to_string(root.join("proc/1/comm")).ok()?;
+    let comm = comm.trim();
+    // reading the executable link of PID 1 usually needs root privileges
+    let exe = fs::read_link(root.join("proc/1/exe"))
+        .ok()
+        .map(|exe| exe.to_string_lossy().into_owned());
+    let exe_name = exe
+        .as_deref()
+        .and_then(|exe| Path::new(exe).file_name()?.to_str());
+
+    let name = match INITS
+        .iter()
+        .find(|(id, _)| *id == comm || Some(*id) == exe_name)
+    {
+        Some((_, name)) => name.to_string(),
+        None if comm == "init" && root.join("run/openrc").is_dir() => "OpenRC".to_string(),
+        None if comm == "init" => "SysVinit".to_string(),
+        None => comm.to_string(),
+    };
+    Some((name, exe))
+}
+
+/// The version of an init system, if it can be found without running it
+///
+/// The version of systemd is part of the file name of its shared library, like
+/// `libsystemd-shared-252.so`.
+pub fn version(root: &Path, name: &str) -> Option<String> {
+    if name != "systemd" {
+        return None;
+    }
+    ["usr/lib/systemd", "lib/systemd", "usr/lib64/systemd"]
+        .iter()
+        .filter_map(|dir| fs::read_dir(root.join(dir)).ok())
+        .flat_map(|entries| entries.flatten())
+        .find_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let version = name
+                .strip_prefix("libsystemd-shared-")?
+                .strip_suffix(".so")?;
+            // distributions may append their own package revision, like `255.4-1-arch`
+            Some(version.split('-').next()?.to_string())
+        })
+}
+
+/// The number of failed systemd units
+///
+/// systemd keeps unit states in memory only, so they are queried from `systemctl`.
+pub fn failed_units(deadline: Instant) -> Option<usize> {
+    let output = command::run(
+        Command::new("systemctl").args(["list-units", "--state=failed", "--no-legend", "--plain"]),
+        deadline,
+    )?;
+    Some(
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn detects_by_process_name() {
+        let cases = [
+            ("systemd\n", false, "systemd"),
+            ("runit\n", false, "runit"),
+            ("s6-svscan\n", false, "s6"),
+            ("init\n", true, "OpenRC"),
+            ("init\n", false, "SysVinit"),
+            ("bash\n", false, "bash"),
+        ];
+        for (index, (comm, openrc, expected)) in cases.into_iter().enumerate() {
+            let root = FakeRoot::new(&format!("init-detect-{index}"));
+            root.file("proc/1/comm", comm);
+            if openrc {
+                root.file("run/openrc/softlevel", "default\n");
+            }
+            let (name, exe) = detect(root.path()).unwrap();
+            assert_eq!(name, expected);
+            assert_eq!(exe, None);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detects_by_executable() {
+        let root = FakeRoot::new("init-exe");
+        root.file("proc/1/comm", "init\n");
+        std::os::unix::fs::symlink("/sbin/openrc-init", root.path().join("proc/1/exe")).unwrap();
+        assert_eq!(
+            detect(root.path()),
+            Some(("OpenRC".to_string(), Some("/sbin/openrc-init".to_string())))
+        );
+    }
+
+    #[test]
+    fn systemd_version() {
+        let root = FakeRoot::new("init-version");
+        assert_eq!(detect(root.path()), None);
+        assert_eq!(version(root.path(), "systemd"), None);
+        root.file("usr/lib/systemd/libsystemd-shared-255.4-1-arch.so", "")
+            .file("usr/lib/systemd/systemd", "");
+        assert_eq!(version(root.path(), "systemd").as_deref(), Some("255.4"));
+        assert_eq!(version(root.path(), "runit"), None);
+
+        let root = FakeRoot::new("init-version-lib64");
+        root.file("usr/lib64/systemd/libsystemd-shared-252.so", "");
+        assert_eq!(version(root.path(), "systemd").as_deref(), Some("252"));
+    }
+}
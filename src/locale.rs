@@ -0,0 +1,141 @@
+use std::env;
+
+use crate::cli::Info;
+
+/// Translated labels of the builtin infos by language code
+///
+/// Infos missing from a translation keep their english label.
+const TRANSLATIONS: &[(&str, &[(Info, &str)])] = &[
+    (
+        "de",
+        &[
+            (Info::Host, "Gerät"),
+            (Info::Virtualization, "Virtualisierung"),
+            (Info::Uptime, "Laufzeit"),
+            (Info::Load, "Last"),
+            (Info::Processes, "Prozesse"),
+            (Info::Users, "Benutzer"),
+            (Info::Packages, "Pakete"),
+            (Info::Theme, "Design"),
+            (Info::Icons, "Symbole"),
+            (Info::Cursor, "Zeiger"),
+            (Info::Font, "Schrift"),
+            (Info::Memory, "Speicher"),
+            (Info::Disk, "Datenträger"),
+            (Info::LocalIP, "Lokale IP"),
+            (Info::PublicIP, "Öffentliche IP"),
+            (Info::Network, "Netzwerk"),
+            (Info::Battery, "Akku"),
+        ],
+    ),
+    (
+        "es",
+        &[
+            (Info::OS, "SO"),
+            (Info::Host, "Equipo"),
+            (Info::Kernel, "Núcleo"),
+            (Info::Virtualization, "Virtualización"),
+            (Info::Uptime, "Activo"),
+            (Info::Load, "Carga"),
+            (Info::Processes, "Procesos"),
+            (Info::Users, "Usuarios"),
+            (Info::Packages, "Paquetes"),
+            (Info::Theme, "Tema"),
+            (Info::Icons, "Iconos"),
+            (Info::Font, "Fuente"),
+            (Info::Memory, "Memoria"),
+            (Info::Disk, "Disco"),
+            (Info::LocalIP, "IP local"),
+            (Info::PublicIP, "IP pública"),
+            (Info::Network, "Red"),
+            (Info::Battery, "Batería"),
+        ],
+    ),
+    (
+        "fr",
+        &[
+            (Info::Host, "Hôte"),
+            (Info::Kernel, "Noyau"),
+            (Info::Virtualization, "Virtualisation"),
+            (Info::Uptime, "Durée"),
+            (Info::Load, "Charge"),
+            (Info::Users, "Utilisateurs"),
+            (Info::Packages, "Paquets"),
+            (Info::Theme, "Thème"),
+            (Info::Icons, "Icônes"),
+            (Info::Cursor, "Curseur"),
+            (Info::Font, "Police"),
+            (Info::Memory, "Mémoire"),
+            (Info::Disk, "Disque"),
+            (Info::LocalIP, "IP locale"),
+            (Info::PublicIP, "IP publique"),
+            (Info::Network, "Réseau"),
+            (Info::Battery, "Batterie"),
+        ],
+    ),
+    (
+        "it",
+        &[
+            (Info::OS, "SO"),
+            (Info::Virtualization, "Virtualizzazione"),
+            (Info::Uptime, "Attività"),
+            (Info::Load, "Carico"),
+            (Info::Processes, "Processi"),
+            (Info::Users, "Utenti"),
+            (Info::Packages, "Pacchetti"),
+            (Info::Theme, "Tema"),
+            (Info::Icons, "Icone"),
+            (Info::Cursor, "Cursore"),
+            (Info::Font, "Carattere"),
+            (Info::Memory, "Memoria"),
+            (Info::Disk, "Disco"),
+            (Info::LocalIP, "IP locale"),
+            (Info::PublicIP, "IP pubblico"),
+            (Info::Network, "Rete"),
+            (Info::Battery, "Batteria"),
+        ],
+    ),
+    (
+        "pt",
+        &[
+            (Info::OS, "SO"),
+            (Info::Host, "Máquina"),
+            (Info::Virtualization, "Virtualização"),
+            (Info::Uptime, "Ativo"),
+            (Info::Load, "Carga"),
+            (Info::Processes, "Processos"),
+            (Info::Users, "Usuários"),
+            (Info::Packages, "Pacotes"),
+            (Info::Theme, "Tema"),
+            (Info::Icons, "Ícones"),
+            (Info::Font, "Fonte"),
+            (Info::Memory, "Memória"),
+            (Info::Disk, "Disco"),
+            (Info::LocalIP, "IP local"),
+            (Info::PublicIP, "IP pública"),
+            (Info::Network, "Rede"),
+            (Info::Battery, "Bateria"),
+        ],
+    ),
+];
+
+/// The language code of the current locale, taken from `LC_ALL`, `LC_MESSAGES` or `LANG`
+pub fn current_language() -> Option<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok().filter(|locale| !locale.is_empty()))?;
+    let language = locale.split(['_', '.', '@']).next()?.to_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => None,
+        _ => Some(language),
+    }
+}
+
+/// The builtin translation of an info label, if there is one
+pub fn translate(info: Info, language: &str) -> Option<&'static str> {
+    let (_, labels) = TRANSLATIONS.iter().find(|(lang, _)| *lang == language)?;
+    labels
+        .iter()
+        .find(|(i, _)| *i == info)
+        .map(|(_, label)| *label)
+}
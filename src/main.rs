@@ -1,22 +1,49 @@
 #![doc = include_str!("../README.md")]
 
-use std::{fs::File, io::Read, process};
+use std::{
+    fs::File,
+    io::Read,
+    process,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use ansipix::FilterType;
 use cache::{read_cache, write_cache};
 use clap::Parser;
-use cli::{Config, Info};
+use cli::{ColorMode, Config, Format, ImageBackend, Info, InfoEntry, ShellMode, UnitSystem};
 use config::expand_path;
+use info::InfoValue;
 
 mod cache;
 mod cli;
+mod command;
 mod config;
+mod cpu;
+mod desktop;
 mod distro;
 mod error;
+mod gpu;
+mod graphics;
+mod http;
 mod info;
+mod init;
+mod locale;
+mod network;
+mod output;
+mod packages;
+mod power;
+mod shell;
+mod template;
+mod terminal;
+#[cfg(test)]
+mod testing;
+mod theme;
+mod units;
+mod users;
+mod virt;
 
 pub use error::Result;
-use strum::IntoEnumIterator;
 
 pub const DEFAULT_MAX_WIDTH: u8 = 30;
 pub const DEFAULT_ALPHA_THRESHOLD: u8 = 50;
@@ -24,9 +51,52 @@ pub const DEFAULT_ALIASING: bool = false;
 pub const DEFAULT_SKIP_CACHE: bool = false;
 pub const DEFAULT_SHOW_COLONS: bool = true;
 pub const DEFAULT_GAP: u8 = 2;
+pub const DEFAULT_FORMAT: Format = Format::Pretty;
+pub const DEFAULT_CUSTOM_TIMEOUT: u64 = 1000;
+pub const DEFAULT_INFO_TIMEOUT: u64 = 2000;
+pub const DEFAULT_IMAGE_BACKEND: ImageBackend = ImageBackend::Auto;
+pub const DEFAULT_COLOR: ColorMode = ColorMode::Auto;
+pub const DEFAULT_ASCII_COLORS: bool = true;
+pub const DEFAULT_UNIT_SYSTEM: UnitSystem = UnitSystem::Iec;
+pub const DEFAULT_SHOW_PERCENT: bool = false;
+pub const DEFAULT_DISK_MOUNTS: &[&str] = &["/", "/home"];
+pub const DEFAULT_HIDDEN_INTERFACES: &[&str] = &["lo", "docker", "veth", "br-", "virbr"];
+pub const DEFAULT_PUBLIC_IP_TTL: u64 = 3600;
+pub const DEFAULT_PUBLIC_IP_TIMEOUT: u64 = 1000;
+pub const DEFAULT_SHOW_FAILED_UNITS: bool = false;
+pub const DEFAULT_SHELL: ShellMode = ShellMode::Current;
+pub const DEFAULT_INFO_ORDER: &[Info] = &[
+    Info::UserAtHostname,
+    Info::OS,
+    Info::Host,
+    Info::Virtualization,
+    Info::Kernel,
+    Info::Uptime,
+    Info::Init,
+    Info::Load,
+    Info::Users,
+    Info::Packages,
+    Info::Shell,
+    Info::DE,
+    Info::Session,
+    Info::Theme,
+    Info::Icons,
+    Info::Cursor,
+    Info::Font,
+    Info::CPU,
+    Info::GPU,
+    Info::Memory,
+    Info::Swap,
+    Info::Disk,
+    Info::LocalIP,
+    Info::PublicIP,
+    Info::Battery,
+    Info::Seperator,
+    Info::Colors1,
+    Info::Colors2,
+];
 
 fn main() {
-    let mut sys = info::System::new();
     let flags = Config::parse();
     let config = match config::read_config() {
         Ok(conf) => match (Config {
@@ -38,8 +108,27 @@ fn main() {
             gap: flags.gap.or(conf.gap),
             color_override: flags.color_override.or(conf.color_override),
             image_override: flags.image_override.or(conf.image_override),
+            info_order: flags.info_order.or(conf.info_order),
             info_whitelist: flags.info_whitelist.or(conf.info_whitelist),
             info_blacklist: flags.info_blacklist.or(conf.info_blacklist),
+            format: flags.format.or(conf.format),
+            info_timeout: flags.info_timeout.or(conf.info_timeout),
+            timeout_placeholder: flags.timeout_placeholder.or(conf.timeout_placeholder),
+            image_backend: flags.image_backend.or(conf.image_backend),
+            ascii_colors: flags.ascii_colors.or(conf.ascii_colors),
+            color: flags.color.or(conf.color),
+            language: flags.language.or(conf.language),
+            unit_system: flags.unit_system.or(conf.unit_system),
+            show_percent: flags.show_percent.or(conf.show_percent),
+            disk_mounts: flags.disk_mounts.or(conf.disk_mounts),
+            hidden_interfaces: flags.hidden_interfaces.or(conf.hidden_interfaces),
+            public_ip_url: flags.public_ip_url.or(conf.public_ip_url),
+            public_ip_ttl: flags.public_ip_ttl.or(conf.public_ip_ttl),
+            show_failed_units: flags.show_failed_units.or(conf.show_failed_units),
+            shell: flags.shell.or(conf.shell),
+            labels: flags.labels.or(conf.labels),
+            templates: flags.templates.or(conf.templates),
+            custom_infos: flags.custom_infos.or(conf.custom_infos),
         }
         .validated())
         {
@@ -55,7 +144,32 @@ fn main() {
         }
     };
 
-    let infos: Vec<_> = Info::iter()
+    let order: Vec<InfoEntry> = match &config.info_order {
+        Some(order) => order.clone(),
+        None => {
+            // custom infos are placed right before the seperator by default
+            let mut order: Vec<_> = DEFAULT_INFO_ORDER
+                .iter()
+                .copied()
+                .map(InfoEntry::Builtin)
+                .collect();
+            let index = order
+                .iter()
+                .position(|i| *i == InfoEntry::Builtin(Info::Seperator))
+                .unwrap_or(order.len());
+            order.splice(
+                index..index,
+                config
+                    .custom_infos
+                    .iter()
+                    .flatten()
+                    .map(|c| InfoEntry::Custom(c.name.clone())),
+            );
+            order
+        }
+    };
+    let infos: Vec<_> = order
+        .into_iter()
         .filter(|i| {
             if let Some(whitelist) = &config.info_whitelist {
                 whitelist.contains(i)
@@ -71,13 +185,20 @@ fn main() {
             }
         })
         .collect();
-    let infos: Vec<(Info, String)> = infos
-        .iter()
-        .map(|i| (i, i.get_info(&mut sys)))
-        .filter(|i| i.1.is_some())
-        .map(|i| (*i.0, i.1.unwrap()))
-        .collect();
+    let config = Arc::new(config);
+    let sys = Arc::new(info::System::new(
+        Instant::now() + Duration::from_millis(config.info_timeout.unwrap_or(DEFAULT_INFO_TIMEOUT)),
+    ));
+    let infos: Vec<(InfoEntry, InfoValue)> =
+        info::collect(infos, Arc::clone(&sys), Arc::clone(&config));
 
+    if config.format.unwrap_or(DEFAULT_FORMAT) == Format::Json {
+        if let Err(e) = output::print_json(&infos, &config) {
+            eprintln!("\x1b[1;31mFailed to print JSON:\x1b[22m {}\x1b[0m", e);
+            process::exit(1);
+        }
+        return;
+    }
     let (mut col, img_bytes) = distro::get_distro_image(sys.os());
     let mut buf = vec![];
     let img_bytes = if let Some(path) = &config.image_override {
@@ -104,20 +225,83 @@ fn main() {
     };
 
     let max_width = config.max_width.unwrap_or(DEFAULT_MAX_WIDTH).into();
-    let (img_str, used_cache) = if let Some(cache) = read_cache(&config, img_bytes) {
+    let alpha_threshold = config.alpha_threshold.unwrap_or(DEFAULT_ALPHA_THRESHOLD);
+    let filter = if config.aliasing.unwrap_or(DEFAULT_ALIASING) {
+        FilterType::CatmullRom
+    } else {
+        FilterType::Nearest
+    };
+    let gap = " ".repeat(config.gap.unwrap_or(DEFAULT_GAP).into());
+    let backend = config.image_backend.unwrap_or(DEFAULT_IMAGE_BACKEND);
+
+    if !output::use_color(config.color.unwrap_or(DEFAULT_COLOR)) {
+        let (img, used_cache) = if backend != ImageBackend::Ascii {
+            (String::new(), true)
+        } else if let Some(cache) = read_cache(&config, backend, false, img_bytes) {
+            (cache.image, true)
+        } else {
+            let img =
+                graphics::render_ascii(img_bytes, max_width as u32, alpha_threshold, filter, false)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to create image ASCII art: {}", e);
+                        process::exit(1);
+                    });
+            (img, false)
+        };
+        output::print_plain(&infos, &config, &img, &gap);
+        if !used_cache {
+            write_cache(&config, backend, false, img_bytes, img);
+        }
+        return;
+    }
+
+    let backend = graphics::resolve_backend(backend);
+    let ascii_colors = config.ascii_colors.unwrap_or(DEFAULT_ASCII_COLORS);
+    let graphic = match backend {
+        ImageBackend::Kitty | ImageBackend::Sixel => {
+            match graphics::render_graphic(
+                img_bytes,
+                backend,
+                max_width as u32,
+                alpha_threshold,
+                filter,
+            ) {
+                Ok(graphic) => Some(graphic),
+                Err(e) => {
+                    eprintln!(
+                        "\x1b[1;31mFailed to create image graphic:\x1b[22m {}\x1b[0m",
+                        e
+                    );
+                    process::exit(1);
+                }
+            }
+        }
+        _ => None,
+    };
+    let (img_str, used_cache) = if graphic.is_some() {
+        // graphics are drawn separately and not cached
+        (String::new(), true)
+    } else if let Some(cache) = read_cache(&config, backend, ascii_colors, img_bytes) {
         (cache.image, true)
     } else {
-        match ansipix::of_image_bytes_with_filter(
-            img_bytes,
-            (max_width, 1000),
-            config.alpha_threshold.unwrap_or(DEFAULT_ALPHA_THRESHOLD),
-            false,
-            if config.aliasing.unwrap_or(DEFAULT_ALIASING) {
-                FilterType::CatmullRom
-            } else {
-                FilterType::Nearest
-            },
-        ) {
+        let img = if backend == ImageBackend::Ascii {
+            graphics::render_ascii(
+                img_bytes,
+                max_width as u32,
+                alpha_threshold,
+                filter,
+                ascii_colors,
+            )
+        } else {
+            ansipix::of_image_bytes_with_filter(
+                img_bytes,
+                (max_width, 1000),
+                alpha_threshold,
+                false,
+                filter,
+            )
+        };
+        match img {
             Ok(img) => (img, false),
             Err(e) => {
                 eprintln!(
@@ -132,43 +316,80 @@ fn main() {
     if let Some(color_override) = config.color_override {
         col = color_override;
     }
-    let img: Vec<&str> = img_str.trim_matches('\n').split('\n').collect();
-    let gap = " ".repeat(config.gap.unwrap_or(DEFAULT_GAP).into());
+    let img: Vec<&str> = match graphic {
+        Some(_) => vec![],
+        None => img_str.trim_matches('\n').split('\n').collect(),
+    };
+    let img_rows = graphic.as_ref().map_or(img.len(), |graphic| graphic.rows);
+
+    if let Some(graphic) = &graphic {
+        // reserve the lines first so the terminal does not scroll while drawing, then return to
+        // the first line after drawing, where the infos are printed next to the image
+        print!(
+            "{}\x1b[{}A\x1b7{gap}{}\x1b8",
+            "\n".repeat(graphic.rows),
+            graphic.rows,
+            graphic.data
+        );
+    }
+
+    let show_colons = config.show_colons.unwrap_or(DEFAULT_SHOW_COLONS);
+    let labels: Vec<String> = infos
+        .iter()
+        .map(|(info, _)| {
+            format!(
+                "{}{}",
+                config.label(info),
+                if show_colons { ":" } else { "" }
+            )
+        })
+        .collect();
+    // align all values, but keep the width of the default labels as the minimum
+    let label_width = infos
+        .iter()
+        .zip(&labels)
+        .filter(|((info, _), _)| info.is_labeled())
+        .map(|(_, label)| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(9);
 
-    for line in 0..(img.len().max(infos.len())) {
+    // one row per line of each value, where only the first line shows the label
+    let rows: Vec<(&InfoEntry, &str, &str)> = infos
+        .iter()
+        .zip(&labels)
+        .flat_map(|((info, value), label)| {
+            value
+                .text
+                .split('\n')
+                .enumerate()
+                .map(move |(index, text)| {
+                    (info, if index == 0 { label.as_str() } else { "" }, text)
+                })
+        })
+        .collect();
+
+    for line in 0..(img_rows.max(rows.len())) {
         if line < img.len() {
             print!("{gap}{}{gap}", img[line]);
         } else {
             print!("{gap}{}{gap}", " ".repeat(max_width));
         }
 
-        if line < infos.len() {
-            #[allow(clippy::format_in_format_args)]
-            if infos[line].0 == Info::UserAtHostname {
-                print!("\x1b[1;3{}m{}\x1b[0m", (col + 1) % 8, infos[line].1);
-            } else if infos[line].0 == Info::Colors1 || infos[line].0 == Info::Colors2 {
-                print!("{}", infos[line].1)
-            } else if infos[line].0 != Info::Seperator {
-                print!(
-                    "\x1b[1;3{}m{: <9}\x1b[0m {}",
-                    col,
-                    format!(
-                        "{:?}{}",
-                        infos[line].0,
-                        if config.show_colons.unwrap_or(DEFAULT_SHOW_COLONS) {
-                            ":"
-                        } else {
-                            ""
-                        }
-                    ),
-                    infos[line].1
-                );
+        if let Some((info, label, text)) = rows.get(line) {
+            match info {
+                InfoEntry::Builtin(Info::UserAtHostname) => {
+                    print!("\x1b[1;3{}m{}\x1b[0m", (col + 1) % 8, text)
+                }
+                InfoEntry::Builtin(Info::Colors1 | Info::Colors2) => print!("{}", text),
+                InfoEntry::Builtin(Info::Seperator) => {}
+                _ => print!("\x1b[1;3{}m{: <label_width$}\x1b[0m {}", col, label, text),
             }
         }
         println!();
     }
 
     if !used_cache {
-        write_cache(&config, img_bytes, img_str);
+        write_cache(&config, backend, ascii_colors, img_bytes, img_str);
     }
 }
@@ -0,0 +1,176 @@
+use std::{fmt, fs, net, path::Path, time::Duration};
+
+use systemstat::{IpAddr, NetworkAddrs};
+
+use crate::{cache, http};
+
+/// An address of a network interface with its prefix length, like `192.168.1.5/24`
+pub struct Address {
+    pub addr: net::IpAddr,
+    pub prefix: u32,
+}
+
+impl Address {
+    pub fn from_systemstat(addr: &NetworkAddrs) -> Option<Self> {
+        let (addr, prefix) = match (&addr.addr, &addr.netmask) {
+            (IpAddr::V4(addr), IpAddr::V4(netmask)) => {
+                (net::IpAddr::V4(*addr), u32::from(*netmask).count_ones())
+            }
+            (IpAddr::V4(addr), _) => (net::IpAddr::V4(*addr), 32),
+            (IpAddr::V6(addr), IpAddr::V6(netmask)) => {
+                (net::IpAddr::V6(*addr), u128::from(*netmask).count_ones())
+            }
+            (IpAddr::V6(addr), _) => (net::IpAddr::V6(*addr), 128),
+            _ => return None,
+        };
+        Some(Self { addr, prefix })
+    }
+
+    /// Whether the address is only valid on its link, like `fe80::1`
+    pub fn is_link_local(&self) -> bool {
+        match self.addr {
+            net::IpAddr::V4(addr) => addr.is_link_local(),
+            net::IpAddr::V6(addr) => (addr.segments()[0] & 0xffc0) == 0xfe80,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Whether an interface is hidden by one of the given name prefixes, like `veth` or `docker`
+pub fn is_hidden(interface: &str, hidden: &[&str]) -> bool {
+    hidden
+        .iter()
+        .any(|prefix| !prefix.is_empty() && interface.starts_with(prefix))
+}
+
+/// The operational state, MAC address and link speed in Mb/s of an interface, read from
+/// `sys/class/net` relative to the root directory
+pub fn link(root: &Path, interface: &str) -> (Option<String>, Option<String>, Option<u64>) {
+    let dir = root.join("sys/class/net").join(interface);
+    let read = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let state = read("operstate");
+    let mac = read("address").filter(|mac| mac != "00:00:00:00:00:00");
+    // the speed is -1 or unreadable while the link is down
+    let speed = read("speed").and_then(|speed| speed.parse().ok());
+    (state, mac, speed)
+}
+
+/// The public IP returned by the plain text endpoint at `url`, cached in `cache_dir` for `ttl`
+/// seconds
+///
+/// Only responses which are a plain address are accepted, so error pages are never shown.
+pub fn public_ip(
+    url: &str,
+    ttl: u64,
+    timeout: Duration,
+    cache_dir: Option<&Path>,
+) -> Option<String> {
+    let cache_dir = cache_dir.filter(|_| ttl > 0);
+    if let Some(ip) = cache_dir.and_then(|dir| cache::read_public_ip(dir, url, ttl)) {
+        return Some(ip);
+    }
+    let ip = http::get(url, timeout)?
+        .trim()
+        .parse::<net::IpAddr>()
+        .ok()?
+        .to_string();
+    if let Some(dir) = cache_dir {
+        cache::write_public_ip(dir, url, &ip);
+    }
+    Some(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http::tests::serve, testing::FakeRoot};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn hidden_interfaces() {
+        let hidden = ["lo", "veth", ""];
+        assert!(is_hidden("lo", &hidden));
+        assert!(is_hidden("veth1a2b3c", &hidden));
+        assert!(!is_hidden("eth0", &hidden));
+        assert!(!is_hidden("wlan0", &[""]));
+    }
+    #[test]
+    fn link_state() {
+        let root = FakeRoot::new("network-link");
+        root.file("sys/class/net/eth0/operstate", "up\n")
+            .file("sys/class/net/eth0/address", "52:54:00:12:34:56\n")
+            .file("sys/class/net/eth0/speed", "1000\n")
+            .file("sys/class/net/wlan0/operstate", "down\n")
+            .file("sys/class/net/wlan0/address", "00:00:00:00:00:00\n")
+            .file("sys/class/net/wlan0/speed", "-1\n");
+        assert_eq!(
+            link(root.path(), "eth0"),
+            (
+                Some("up".to_string()),
+                Some("52:54:00:12:34:56".to_string()),
+                Some(1000)
+            )
+        );
+        assert_eq!(
+            link(root.path(), "wlan0"),
+            (Some("down".to_string()), None, None)
+        );
+        assert_eq!(link(root.path(), "tun0"), (None, None, None));
+    }
+
+    #[test]
+    fn public_ip_plain_address() {
+        let url = serve(Some("HTTP/1.0 200 OK\r\n\r\n 203.0.113.7\n"));
+        assert_eq!(
+            public_ip(&url, 0, TIMEOUT, None).as_deref(),
+            Some("203.0.113.7")
+        );
+        let url = serve(Some("HTTP/1.0 200 OK\r\n\r\n2001:db8::1\n"));
+        assert_eq!(
+            public_ip(&url, 0, TIMEOUT, None).as_deref(),
+            Some("2001:db8::1")
+        );
+    }
+
+    #[test]
+    fn public_ip_rejects_errors() {
+        let url = serve(Some(
+            "HTTP/1.0 500 Internal Server Error\r\n\r\n203.0.113.7",
+        ));
+        assert_eq!(public_ip(&url, 0, TIMEOUT, None), None);
+        let url = serve(Some(
+            "HTTP/1.0 200 OK\r\nContent-Type: text/html\r\n\r\n<html>rate limited</html>",
+        ));
+        assert_eq!(public_ip(&url, 0, TIMEOUT, None), None);
+    }
+
+    #[test]
+    fn public_ip_timeout() {
+        let url = serve(None);
+        assert_eq!(public_ip(&url, 0, Duration::from_millis(200), None), None);
+    }
+
+    #[test]
+    fn public_ip_cached() {
+        let cache = FakeRoot::new("network-public-ip");
+        // the server only answers once, so the second lookup has to come from the cache
+        let url = serve(Some("HTTP/1.0 200 OK\r\n\r\n203.0.113.7\n"));
+        for _ in 0..2 {
+            assert_eq!(
+                public_ip(&url, 3600, TIMEOUT, Some(cache.path())).as_deref(),
+                Some("203.0.113.7")
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    cli::{ColorMode, Config, Info, InfoEntry},
+    info::{self, Field, InfoValue},
+};
+
+#[derive(Serialize)]
+struct JsonInfo<'a> {
+    info: String,
+    label: String,
+    value: &'a str,
+    #[serde(serialize_with = "info::serialize_fields")]
+    raw: &'a [(String, Field)],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    items: Vec<JsonItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonItem<'a> {
+    value: &'a str,
+    #[serde(serialize_with = "info::serialize_fields")]
+    raw: &'a [(String, Field)],
+}
+
+/// Prints all infos with their raw values as a JSON array to stdout
+///
+/// Infos that only exist for layout purposes are skipped
+pub fn print_json(infos: &[(InfoEntry, InfoValue)], config: &Config) -> serde_json::Result<()> {
+    let infos: Vec<_> = infos
+        .iter()
+        .filter(|(info, _)| {
+            ![Info::Seperator, Info::Colors1, Info::Colors2]
+                .map(InfoEntry::Builtin)
+                .contains(info)
+        })
+        .map(|(info, value)| JsonInfo {
+            info: info.name(),
+            label: config.label(info),
+            value: &value.text,
+            raw: &value.fields,
+            items: value
+                .items
+                .iter()
+                .map(|item| JsonItem {
+                    value: &item.text,
+                    raw: &item.fields,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &infos)?;
+    writeln!(stdout).map_err(serde_json::Error::io)
+}
+
+/// Whether colored output should be used, respecting `NO_COLOR` and whether stdout is a terminal
+pub fn use_color(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            !matches!(env::var_os("NO_COLOR"), Some(v) if !v.is_empty())
+                && io::stdout().is_terminal()
+        }
+    }
+}
+
+/// Prints all infos as plain `Key: value` lines without any escape sequences
+///
+/// When `image` is not empty, its lines are printed to the left of the infos.
+pub fn print_plain(infos: &[(InfoEntry, InfoValue)], config: &Config, image: &str, gap: &str) {
+    let show_colons = config.show_colons.unwrap_or(crate::DEFAULT_SHOW_COLONS);
+    let image: Vec<&str> = image.lines().collect();
+    let image_width = image.iter().map(|line| line.len()).max().unwrap_or(0);
+    let lines: Vec<String> = infos
+        .iter()
+        .flat_map(|(info, value)| {
+            let label = format!(
+                "{}{}",
+                config.label(info),
+                if show_colons { ":" } else { "" }
+            );
+            value
+                .text
+                .split('\n')
+                .enumerate()
+                .filter_map(move |(index, text)| match info {
+                    InfoEntry::Builtin(Info::Colors1 | Info::Colors2) => None,
+                    InfoEntry::Builtin(Info::UserAtHostname | Info::Seperator) => {
+                        Some(text.to_owned())
+                    }
+                    // further lines of a value are aligned with its first line
+                    _ if index > 0 => Some(format!("{: <1$} {text}", "", label.chars().count())),
+                    _ => Some(format!("{label} {text}")),
+                })
+        })
+        .collect();
+
+    for line in 0..image.len().max(lines.len()) {
+        if !image.is_empty() {
+            print!(
+                "{gap}{: <image_width$}{gap}",
+                image.get(line).unwrap_or(&"")
+            );
+        }
+        println!("{}", lines.get(line).map_or("", |line| line.as_str()));
+    }
+}
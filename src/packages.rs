@@ -0,0 +1,361 @@
+use std::{
+    env, fs,
+    path::Path,
+    process::Command,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::command;
+
+/// How long before the deadline of the info the package manager commands are stopped, so the
+/// counts found until then can still be shown
+const PROBE_MARGIN: Duration = Duration::from_millis(50);
+
+/// A package manager and the command listing its installed packages
+///
+/// When a `native` function is given, it is used to read the package database directly
+/// relative to the given root directory, falling back to the command when it returns `None`.
+/// Managers without a command are only read natively.
+struct PackageManager {
+    name: &'static str,
+    command: &'static str,
+    args: &'static [&'static str],
+    count: fn(&str) -> usize,
+    native: Option<fn(&Path) -> Option<usize>>,
+}
+
+impl PackageManager {
+    const fn new(name: &'static str, command: &'static str, args: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            command,
+            args,
+            count: count_lines,
+            native: None,
+        }
+    }
+
+    const fn native(name: &'static str, native: fn(&Path) -> Option<usize>) -> Self {
+        Self::new(name, "", &[]).with_native(native)
+    }
+
+    const fn with_native(mut self, native: fn(&Path) -> Option<usize>) -> Self {
+        self.native = Some(native);
+        self
+    }
+
+    const fn with_count(mut self, count: fn(&str) -> usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    fn read_native(&self) -> Option<usize> {
+        (self.native?)(Path::new("/"))
+    }
+
+    fn has_command(&self) -> bool {
+        !self.command.is_empty() && in_path(self.command)
+    }
+
+    fn probe(&self, deadline: Instant) -> Option<usize> {
+        let home = env::var("HOME");
+        let mut args = Vec::with_capacity(self.args.len());
+        for arg in self.args {
+            args.push(match arg.strip_prefix("~/") {
+                Some(rest) => format!("{}/{}", home.as_ref().ok()?, rest),
+                None => arg.to_string(),
+            });
+        }
+
+        let output = command::run(Command::new(self.command).args(args), deadline)?;
+        Some((self.count)(&output))
+    }
+}
+
+const MANAGERS: &[PackageManager] = &[
+    PackageManager::new("pacman", "pacman", &["-Qq"]).with_native(read_pacman),
+    PackageManager::new("dpkg", "dpkg-query", &["-f", r".\n", "-W"]).with_native(read_dpkg),
+    PackageManager::new("bonsai", "bonsai", &["list"]),
+    PackageManager::new("pkginfo", "pkginfo", &["-i"]),
+    PackageManager::new("rpm", "rpm", &["-qa"]),
+    PackageManager::new("xbps-query", "xbps-query", &["-l"]).with_native(read_xbps),
+    PackageManager::new("apk", "apk", &["info"]).with_native(read_apk),
+    PackageManager::new("guix", "guix", &["package", "--list-installed"]),
+    PackageManager::new("opkg", "opkg", &["list-installed"]),
+    PackageManager::new("kiss", "kiss", &["l"]).with_native(read_kiss),
+    PackageManager::new("cpt-list", "cpt-list", &[]),
+    PackageManager::new("pacman-g2", "pacman-g2", &["-Q"]),
+    PackageManager::new("lvu", "lvu", &["installed"]),
+    PackageManager::new("tce-status", "tce-status", &["-i"]),
+    PackageManager::new("pkg_info", "pkg_info", &[]),
+    PackageManager::new("pkgin", "pkgin", &["list"]),
+    PackageManager::new("sorcery", "gaze", &["installed"]),
+    PackageManager::new("alps", "alps", &["showinstalled"]),
+    PackageManager::new("butch", "butch", &["list"]),
+    PackageManager::new("swupd", "swupd", &["bundle-list", "--quiet"]),
+    PackageManager::new("pisi", "pisi", &["li"]),
+    PackageManager::new("pacstall", "pacstall", &["-L"]),
+    PackageManager::new("flatpak", "flatpak", &["list"]),
+    PackageManager::new("snap", "snap", &["list"]).with_count(count_lines_after_header),
+    PackageManager::new(
+        "nix-system",
+        "nix-store",
+        &["-qR", "/run/current-system/sw"],
+    ),
+    PackageManager::new("nix-user", "nix-store", &["-qR", "~/.nix-profile"]),
+    PackageManager::new(
+        "nix-default",
+        "nix-store",
+        &["-qR", "/nix/var/nix/profiles/default"],
+    ),
+    PackageManager::new("cargo", "cargo", &["install", "--list"])
+        .with_count(count_unindented_lines),
+    PackageManager::new("brew", "brew", &["list", "--formula", "-1"]),
+    PackageManager::native("pip", read_pip),
+];
+
+fn count_lines(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+fn count_lines_after_header(output: &str) -> usize {
+    count_lines(output).saturating_sub(1)
+}
+
+fn count_unindented_lines(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with(char::is_whitespace))
+        .count()
+}
+
+fn count_dirs(path: &Path) -> Option<usize> {
+    Some(
+        fs::read_dir(path)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.file_type().ok())
+            .filter(|file_type| file_type.is_dir())
+            .count(),
+    )
+}
+
+fn read_pacman(root: &Path) -> Option<usize> {
+    count_dirs(&root.join("var/lib/pacman/local"))
+}
+
+fn read_kiss(root: &Path) -> Option<usize> {
+    count_dirs(&root.join("var/db/kiss/installed"))
+}
+
+fn read_dpkg(root: &Path) -> Option<usize> {
+    let status = fs::read_to_string(root.join("var/lib/dpkg/status")).ok()?;
+    Some(
+        status
+            .lines()
+            .filter(|line| *line == "Status: install ok installed")
+            .count(),
+    )
+}
+
+fn read_apk(root: &Path) -> Option<usize> {
+    let installed = fs::read_to_string(root.join("lib/apk/db/installed")).ok()?;
+    Some(
+        installed
+            .lines()
+            .filter(|line| line.starts_with("P:"))
+            .count(),
+    )
+}
+
+fn read_xbps(root: &Path) -> Option<usize> {
+    let db_dir = root.join("var/db/xbps");
+    let pkgdb = fs::read_dir(db_dir).ok()?.find_map(|entry| {
+        let path = entry.ok()?.path();
+        let name = path.file_name()?.to_str()?;
+        (name.starts_with("pkgdb-") && name.ends_with(".plist")).then_some(path)
+    })?;
+    Some(
+        fs::read_to_string(pkgdb)
+            .ok()?
+            .matches("<key>pkgver</key>")
+            .count(),
+    )
+}
+
+/// Counts the Python packages installed with `pip install --user`
+fn read_pip(root: &Path) -> Option<usize> {
+    let home = env::var("HOME").ok()?;
+    count_dist_infos(&root.join(home.trim_start_matches('/')).join(".local/lib"))
+}
+
+/// Counts the `*.dist-info` directories in the `python3*/site-packages` directories in `lib`
+fn count_dist_infos(lib: &Path) -> Option<usize> {
+    let mut count = None;
+    for path in fs::read_dir(lib)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+    {
+        if !path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("python3"))
+        {
+            continue;
+        }
+        let Ok(packages) = fs::read_dir(path.join("site-packages")) else {
+            continue;
+        };
+        *count.get_or_insert(0) += packages
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".dist-info"))
+            .count();
+    }
+    count
+}
+
+/// Whether an executable with this name exists in one of the directories in `PATH`
+fn in_path(command: &str) -> bool {
+    match env::var_os("PATH") {
+        Some(path) => env::split_paths(&path).any(|dir| is_executable(&dir.join(command))),
+        None => false,
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Counts the installed packages of all package managers present on this system
+///
+/// Package databases which can be read directly are preferred over running commands.
+/// Only managers whose command is found in `PATH` are run, all of them in parallel, and those
+/// still running shortly before the `deadline` are left out. Managers without any installed
+/// packages are left out as well.
+pub fn count(deadline: Instant) -> Vec<(&'static str, usize)> {
+    let probe_deadline = deadline.checked_sub(PROBE_MARGIN).unwrap_or(deadline);
+    let mut counts: Vec<_> = MANAGERS.iter().map(PackageManager::read_native).collect();
+
+    let (sender, receiver) = mpsc::channel();
+    for (index, manager) in MANAGERS.iter().enumerate() {
+        if counts[index].is_some() || !manager.has_command() {
+            continue;
+        }
+        let sender = sender.clone();
+        thread::spawn(move || {
+            sender.send((index, manager.probe(probe_deadline))).ok();
+        });
+    }
+    drop(sender);
+    while let Ok((index, count)) =
+        receiver.recv_timeout(probe_deadline.saturating_duration_since(Instant::now()))
+    {
+        counts[index] = count;
+    }
+
+    MANAGERS
+        .iter()
+        .zip(counts)
+        .filter_map(|(manager, count)| match count? {
+            0 => None,
+            count => Some((manager.name, count)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn native_databases() {
+        let root = FakeRoot::new("packages-native");
+        root.file("var/lib/pacman/local/ALPM_DB_VERSION", "9\n")
+            .file("var/lib/pacman/local/bash-5.2.026-2/desc", "")
+            .file("var/lib/pacman/local/glibc-2.39-1/desc", "")
+            .file("var/db/kiss/installed/busybox/version", "1.36.1 1\n")
+            .file(
+                "var/lib/dpkg/status",
+                "Package: bash\nStatus: install ok installed\n\n\
+                 Package: vim\nStatus: deinstall ok config-files\n\n\
+                 Package: zsh\nStatus: install ok installed\n",
+            )
+            .file(
+                "lib/apk/db/installed",
+                "C:Q1abc=\nP:musl\nV:1.2.4-r2\n\nC:Q1def=\nP:busybox\nV:1.36.1-r5\n",
+            )
+            .file(
+                "var/db/xbps/pkgdb-0.38.plist",
+                "<dict><key>pkgver</key><string>bash-5.2</string></dict>\n\
+                 <dict><key>pkgver</key><string>zsh-5.9</string></dict>\n\
+                 <dict><key>pkgver</key><string>vim-9.1</string></dict>\n",
+            );
+
+        assert_eq!(read_pacman(root.path()), Some(2));
+        assert_eq!(read_kiss(root.path()), Some(1));
+        assert_eq!(read_dpkg(root.path()), Some(2));
+        assert_eq!(read_apk(root.path()), Some(2));
+        assert_eq!(read_xbps(root.path()), Some(3));
+    }
+
+    #[test]
+    fn pip_user_packages() {
+        let home = FakeRoot::new("packages-pip");
+        home.file(
+            ".local/lib/python3.11/site-packages/requests-2.31.0.dist-info/METADATA",
+            "",
+        )
+        .file(
+            ".local/lib/python3.12/site-packages/rich-13.7.1.dist-info/METADATA",
+            "",
+        )
+        .file(
+            ".local/lib/python3.12/site-packages/pygments-2.17.2.dist-info/METADATA",
+            "",
+        )
+        .file(".local/lib/python3.12/site-packages/rich/__init__.py", "")
+        .file(
+            ".local/lib/python2.7/site-packages/six-1.16.0.dist-info/METADATA",
+            "",
+        )
+        .file(".local/lib/python3.13/README", "");
+        assert_eq!(count_dist_infos(&home.path().join(".local/lib")), Some(3));
+        assert_eq!(count_dist_infos(&home.path().join("missing")), None);
+    }
+
+    #[test]
+    fn missing_databases() {
+        let root = FakeRoot::new("packages-missing");
+        for read in [read_pacman, read_kiss, read_dpkg, read_apk, read_xbps] {
+            assert_eq!(read(root.path()), None);
+        }
+    }
+
+    #[test]
+    fn command_output() {
+        assert_eq!(count_lines("bash\nzsh\n\n"), 2);
+        assert_eq!(
+            count_lines_after_header("Name  Version\nfirefox  124.0\ncore22  20240111\n"),
+            2
+        );
+        assert_eq!(count_lines_after_header(""), 0);
+        assert_eq!(
+            count_unindented_lines("bat v0.24.0:\n    bat\nripgrep v14.1.0:\n    rg\n"),
+            2
+        );
+    }
+}
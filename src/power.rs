@@ -0,0 +1,202 @@
+use std::{fs, path::Path};
+
+/// A battery in `sys/class/power_supply`, either of the system or of a peripheral like a mouse
+pub struct Battery {
+    pub name: String,
+    pub model: Option<String>,
+    /// Whether the battery powers a peripheral instead of the system
+    pub peripheral: bool,
+    pub percentage: f64,
+    /// Like `Charging`, `Discharging`, `Full` or `Not charging`
+    pub status: Option<String>,
+    /// The power draw in W
+    pub power: Option<f64>,
+    /// The estimated time in minutes until the battery is full or empty, depending on the status
+    pub time_remaining: Option<u64>,
+}
+
+/// Reads all batteries and whether an AC adapter is connected, relative to the root directory
+pub fn read(root: &Path) -> (Vec<Battery>, Option<bool>) {
+    let mut dirs: Vec<_> = match fs::read_dir(root.join("sys/class/power_supply")) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+        Err(_) => return (vec![], None),
+    };
+    dirs.sort();
+
+    let mut batteries = vec![];
+    let mut ac_online = None;
+    for dir in dirs {
+        let read = |file: &str| {
+            fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let number = |file: &str| read(file)?.parse::<f64>().ok();
+        match read("type").as_deref() {
+            Some("Mains") => {
+                ac_online =
+                    Some(ac_online.unwrap_or(false) || read("online").as_deref() == Some("1"))
+            }
+            Some("Battery") => {
+                // values are given in µWh and µW, or in µAh and µA for batteries reporting charge
+                let (now, full, rate) = match number("energy_now") {
+                    Some(now) => (Some(now), number("energy_full"), number("power_now")),
+                    None => (
+                        number("charge_now"),
+                        number("charge_full"),
+                        number("current_now"),
+                    ),
+                };
+                let percentage = number("capacity").or_else(|| Some(now? / full? * 100.0));
+                let Some(percentage) = percentage else {
+                    continue;
+                };
+                let status = read("status");
+                let power = number("power_now")
+                    .or_else(|| Some(number("current_now")? * number("voltage_now")? / 1e6))
+                    .map(|power| power.abs() / 1e6)
+                    .filter(|power| *power > 0.0);
+                let time_remaining = match (status.as_deref(), now, full, rate) {
+                    (Some("Discharging"), Some(now), _, Some(rate)) if rate != 0.0 => {
+                        Some(now / rate.abs())
+                    }
+                    (Some("Charging"), Some(now), Some(full), Some(rate)) if rate != 0.0 => {
+                        Some((full - now).max(0.0) / rate.abs())
+                    }
+                    _ => None,
+                }
+                .map(|hours| (hours * 60.0).round() as u64);
+                batteries.push(Battery {
+                    name: dir
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned(),
+                    model: read("model_name"),
+                    peripheral: read("scope").as_deref() == Some("Device"),
+                    percentage,
+                    status,
+                    power,
+                    time_remaining,
+                });
+            }
+            _ => {}
+        }
+    }
+    (batteries, ac_online)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    fn supply(root: &FakeRoot, name: &str, files: &[(&str, &str)]) {
+        for (file, value) in files {
+            root.file(&format!("sys/class/power_supply/{name}/{file}"), value);
+        }
+    }
+
+    #[test]
+    fn energy_and_charge_batteries() {
+        let root = FakeRoot::new("power-batteries");
+        // 30 Wh of 60 Wh at 15 W
+        supply(
+            &root,
+            "BAT0",
+            &[
+                ("type", "Battery\n"),
+                ("status", "Discharging\n"),
+                ("capacity", "50\n"),
+                ("energy_now", "30000000\n"),
+                ("energy_full", "60000000\n"),
+                ("power_now", "15000000\n"),
+                ("model_name", "5B10W\n"),
+            ],
+        );
+        // 3 Ah of 4 Ah at 1 A and 12 V, without a capacity
+        supply(
+            &root,
+            "BAT1",
+            &[
+                ("type", "Battery\n"),
+                ("status", "Charging\n"),
+                ("charge_now", "3000000\n"),
+                ("charge_full", "4000000\n"),
+                ("current_now", "1000000\n"),
+                ("voltage_now", "12000000\n"),
+            ],
+        );
+        supply(
+            &root,
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery\n"),
+                ("scope", "Device\n"),
+                ("status", "Discharging\n"),
+                ("capacity", "80\n"),
+                ("model_name", "MX Master 3\n"),
+            ],
+        );
+        supply(&root, "AC", &[("type", "Mains\n"), ("online", "1\n")]);
+
+        let (batteries, ac_online) = read(root.path());
+        assert_eq!(ac_online, Some(true));
+        assert_eq!(batteries.len(), 3);
+
+        let bat0 = &batteries[0];
+        assert_eq!(bat0.name, "BAT0");
+        assert_eq!(bat0.model.as_deref(), Some("5B10W"));
+        assert!(!bat0.peripheral);
+        assert_eq!(bat0.percentage, 50.0);
+        assert_eq!(bat0.status.as_deref(), Some("Discharging"));
+        assert_eq!(bat0.power, Some(15.0));
+        assert_eq!(bat0.time_remaining, Some(120));
+
+        let bat1 = &batteries[1];
+        assert_eq!(bat1.name, "BAT1");
+        assert_eq!(bat1.percentage, 75.0);
+        assert_eq!(bat1.status.as_deref(), Some("Charging"));
+        assert_eq!(bat1.power, Some(12.0));
+        assert_eq!(bat1.time_remaining, Some(60));
+
+        let mouse = &batteries[2];
+        assert_eq!(mouse.model.as_deref(), Some("MX Master 3"));
+        assert!(mouse.peripheral);
+        assert_eq!(mouse.percentage, 80.0);
+        assert_eq!(mouse.power, None);
+        assert_eq!(mouse.time_remaining, None);
+    }
+
+    #[test]
+    fn no_time_remaining_when_full() {
+        let root = FakeRoot::new("power-full");
+        supply(
+            &root,
+            "BAT0",
+            &[
+                ("type", "Battery\n"),
+                ("status", "Full\n"),
+                ("energy_now", "60000000\n"),
+                ("energy_full", "60000000\n"),
+                ("power_now", "0\n"),
+            ],
+        );
+        supply(&root, "ADP1", &[("type", "Mains\n"), ("online", "0\n")]);
+
+        let (batteries, ac_online) = read(root.path());
+        assert_eq!(ac_online, Some(false));
+        assert_eq!(batteries[0].percentage, 100.0);
+        assert_eq!(batteries[0].power, None);
+        assert_eq!(batteries[0].time_remaining, None);
+    }
+
+    #[test]
+    fn no_power_supplies() {
+        let root = FakeRoot::new("power-none");
+        let (batteries, ac_online) = read(root.path());
+        assert!(batteries.is_empty());
+        assert_eq!(ac_online, None);
+    }
+}
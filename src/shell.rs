@@ -0,0 +1,135 @@
+use std::{collections::HashMap, path::Path, process::Command, time::Instant};
+
+use crate::{command, desktop, terminal::Process};
+
+/// Known shells with the arguments printing their version
+const SHELLS: &[(&str, &[&str])] = &[
+    ("bash", &["--version"]),
+    ("zsh", &["--version"]),
+    ("fish", &["--version"]),
+    ("nu", &["--version"]),
+    ("elvish", &["-version"]),
+    ("xonsh", &["--version"]),
+    ("tcsh", &["--version"]),
+    ("ksh", &[]),
+    ("mksh", &[]),
+    ("pwsh", &["--version"]),
+    ("ion", &["--version"]),
+    ("dash", &[]),
+    ("csh", &[]),
+    ("sh", &[]),
+];
+
+/// Processes between the shell and pixfetch, which are walked past
+const PRIVILEGE_TOOLS: &[&str] = &["sudo", "su", "doas", "run0"];
+
+/// Whether a process name belongs to a known shell
+pub fn is_shell(name: &str) -> bool {
+    SHELLS.iter().any(|(shell, _)| *shell == name)
+}
+
+/// Finds the shell running pixfetch by walking up the process tree from `pid`, returning its PID
+///
+/// Privilege tools like `sudo` are walked past, anything else ends the search.
+pub fn find(processes: &HashMap<u32, Process>, pid: u32) -> Option<u32> {
+    let mut pid = processes.get(&pid)?.parent?;
+    loop {
+        let process = processes.get(&pid)?;
+        if is_shell(process.name) {
+            return Some(pid);
+        }
+        if !PRIVILEGE_TOOLS.contains(&process.name) {
+            return None;
+        }
+        pid = process.parent?;
+    }
+}
+
+/// The version of a shell from the output of running the executable at `path`
+///
+/// Variables like `BASH_VERSION` are not exported, so the executable is always run.
+pub fn version(name: &str, path: &Path, deadline: Instant) -> Option<String> {
+    let (_, args) = SHELLS.iter().find(|(shell, _)| *shell == name)?;
+    if args.is_empty() {
+        return None;
+    }
+    let output = command::run(Command::new(path).args(*args), deadline)?;
+    // xonsh prints its version like `xonsh/0.14.0`
+    let output = output.replace('/', " ");
+    Some(clean_version(&desktop::parse_version(&output)?))
+}
+
+/// Cuts versions like `5.2.15(1)-release` down to `5.2.15`
+fn clean_version(version: &str) -> String {
+    version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or(version)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::tests::{with_processes, Tree};
+
+    fn find_in(tree: Tree) -> Option<u32> {
+        with_processes(tree, |processes| find(processes, 1000))
+    }
+
+    #[test]
+    fn finds_shell() {
+        let cases: &[(Tree, Option<u32>)] = &[
+            (
+                &[
+                    (10, "alacritty", Some(1), &[]),
+                    (20, "zsh", Some(10), &[]),
+                    (1000, "pixfetch", Some(20), &[]),
+                ],
+                Some(20),
+            ),
+            (
+                &[
+                    (20, "bash", Some(10), &[]),
+                    (30, "su", Some(20), &[]),
+                    (40, "fish", Some(30), &[]),
+                    (50, "sudo", Some(40), &[]),
+                    (1000, "pixfetch", Some(50), &[]),
+                ],
+                Some(40),
+            ),
+            (
+                &[
+                    (20, "bash", Some(10), &[]),
+                    (30, "sudo", Some(20), &[]),
+                    (40, "su", Some(30), &[]),
+                    (1000, "pixfetch", Some(40), &[]),
+                ],
+                Some(20),
+            ),
+            (
+                &[
+                    (20, "bash", Some(10), &[]),
+                    (30, "make", Some(20), &[]),
+                    (1000, "pixfetch", Some(30), &[]),
+                ],
+                None,
+            ),
+            (
+                &[(30, "sudo", None, &[]), (1000, "pixfetch", Some(30), &[])],
+                None,
+            ),
+        ];
+        for (tree, expected) in cases {
+            assert_eq!(find_in(tree), *expected);
+        }
+    }
+
+    #[test]
+    fn clean_versions() {
+        assert_eq!(clean_version("5.2.15(1)-release"), "5.2.15");
+        assert_eq!(clean_version("5.9"), "5.9");
+        assert_eq!(clean_version("3.7.1-dirty"), "3.7.1");
+        assert_eq!(clean_version(""), "");
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+use crate::info::Field;
+
+/// The number of decimal places floats are shown with, when no precision is specified
+const DEFAULT_PRECISION: usize = 2;
+
+/// Replaces the `{name}` placeholders in a template with the values of the named fields
+///
+/// - `{value}` is replaced with the default text of the info
+/// - `{name:.N}` shows a number with `N` decimal places
+/// - `{{` and `}}` are replaced with literal braces
+/// - placeholders of unknown fields are left unchanged
+pub fn render(template: &str, value: &str, fields: &[(String, Field)]) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        out.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let end = match (rest.starts_with('{'), rest.find('}')) {
+            (true, Some(end)) => end,
+            _ => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let placeholder = &rest[1..end];
+        let (name, precision) = match placeholder.split_once(":.") {
+            Some((name, precision)) => (name, precision.parse().ok()),
+            None => (placeholder, None),
+        };
+        match (name, fields.iter().find(|(n, _)| n == name)) {
+            ("value", _) => out.push_str(value),
+            (_, Some((_, field))) => write_field(&mut out, field, precision),
+            (_, None) => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn write_field(out: &mut String, field: &Field, precision: Option<usize>) {
+    match (field, precision) {
+        (Field::Int(n), None) => write!(out, "{n}"),
+        (Field::Int(n), Some(precision)) => write!(out, "{:.precision$}", *n as f64),
+        (Field::Float(n), precision) => {
+            write!(out, "{n:.0$}", precision.unwrap_or(DEFAULT_PRECISION))
+        }
+        (Field::Str(s), _) => write!(out, "{s}"),
+    }
+    .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<(String, Field)> {
+        vec![
+            ("used".to_string(), Field::Int(512)),
+            ("percent".to_string(), Field::Float(42.1234)),
+            ("unit".to_string(), Field::Str("MiB".to_string())),
+        ]
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            render("{used} {unit} ({value})", "512 MiB", &fields()),
+            "512 MiB (512 MiB)"
+        );
+        assert_eq!(render("{unknown} {used}", "", &fields()), "{unknown} 512");
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(render("{percent}%", "", &fields()), "42.12%");
+        assert_eq!(render("{percent:.0}%", "", &fields()), "42%");
+        assert_eq!(render("{percent:.3}%", "", &fields()), "42.123%");
+        assert_eq!(render("{used:.1}", "", &fields()), "512.0");
+        assert_eq!(render("{unit:.1}", "", &fields()), "MiB");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(render("{{used}} }}{{", "", &fields()), "{used} }{");
+        assert_eq!(render("{{{used}}}", "", &fields()), "{512}");
+    }
+
+    #[test]
+    fn unclosed_braces() {
+        assert_eq!(render("{used", "", &fields()), "{used");
+        assert_eq!(render("used}", "", &fields()), "used}");
+        assert_eq!(render("{used} {", "", &fields()), "512 {");
+    }
+}
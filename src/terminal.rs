@@ -0,0 +1,455 @@
+use std::{collections::HashMap, fs, path::Path, process::Command, time::Instant};
+
+use crate::{command, desktop, shell, theme};
+
+/// Processes between the terminal and the shell, which are walked past like shells
+const WRAPPERS: &[&str] = &["sudo", "su", "doas", "run0", "login", "script", "nix-shell"];
+
+/// A process in a process tree
+pub struct Process<'a> {
+    pub name: &'a str,
+    pub parent: Option<u32>,
+    pub cmd: &'a [String],
+}
+
+/// The multiplexer a process belongs to, if it is one
+///
+/// Multiplexers sit between the terminal and the shell, but do not show its window.
+fn multiplexer(name: &str) -> Option<&'static str> {
+    match name {
+        // the tmux server and client are called like `tmux: server`
+        name if name.starts_with("tmux") => Some("tmux"),
+        "screen" | "SCREEN" => Some("screen"),
+        "zellij" => Some("zellij"),
+        "sshd" | "sshd-session" => Some("sshd"),
+        _ => None,
+    }
+}
+
+/// Finds the terminal pixfetch is running in by walking up the process tree from `pid`
+///
+/// Shells, privilege tools and multiplexers are walked past. As the servers of tmux, screen and
+/// zellij are not children of the terminal, the walk continues at the client attached to them,
+/// which `client` returns for the name of the multiplexer. When no terminal is found above a
+/// multiplexer, like for a remote session, the multiplexer is used.
+pub fn find<'a>(
+    processes: &HashMap<u32, Process<'a>>,
+    pid: u32,
+    client: impl Fn(&str) -> Option<u32>,
+) -> Option<&'a str> {
+    let mut pid = processes.get(&pid)?.parent?;
+    let mut found = None;
+    let mut visited = vec![];
+    loop {
+        let process = processes.get(&pid)?;
+        let name = process.name;
+        if let Some(multiplexer) = multiplexer(name) {
+            found = Some(multiplexer);
+            if !visited.contains(&multiplexer) {
+                visited.push(multiplexer);
+                if let Some(client) = client(multiplexer) {
+                    pid = client;
+                    continue;
+                }
+            }
+        } else if pid == 1 || name == "systemd" || name == "init" {
+            return found;
+        } else if !shell::is_shell(name) && !WRAPPERS.contains(&name) {
+            return Some(match name {
+                // VS Code runs its terminal in an electron process below the `code` process
+                "electron"
+                    if ancestors(processes, pid)
+                        .take(2)
+                        .any(|n| n.contains("code")) =>
+                {
+                    "vscode"
+                }
+                // process names are cut off after 15 characters
+                "gnome-terminal-" => "gnome-terminal",
+                "wezterm-gui" => "wezterm",
+                name => name,
+            });
+        }
+        pid = match process.parent {
+            Some(parent) => parent,
+            None => return found,
+        };
+    }
+}
+
+/// Finds a client attached to a screen or zellij session, given by `STY` or
+/// `ZELLIJ_SESSION_NAME`
+///
+/// Clients are the processes of the multiplexer which are not its server. A client naming the
+/// session in its arguments, like `screen -r 1234` or `zellij attach dev`, is preferred, as other
+/// sessions may be attached in other terminals.
+pub fn client(processes: &HashMap<u32, Process>, multiplexer: &str, session: &str) -> Option<u32> {
+    let mut clients: Vec<_> = processes
+        .iter()
+        .filter(|(_, process)| {
+            // the screen server is called `SCREEN`, the zellij server is started with `--server`
+            process.name == multiplexer && !process.cmd.iter().any(|arg| arg == "--server")
+        })
+        .collect();
+    clients.sort_by_key(|(pid, _)| **pid);
+    let names_session = |arg: &String| {
+        // screen sessions are called like `1234.pts-0.host` and may be referenced by either part
+        !arg.starts_with('-')
+            && (arg == session
+                || session.starts_with(&format!("{arg}."))
+                || session.ends_with(&format!(".{arg}")))
+    };
+    clients
+        .iter()
+        .find(|(_, process)| process.cmd.iter().skip(1).any(names_session))
+        .or_else(|| clients.first())
+        .map(|(pid, _)| **pid)
+}
+
+fn ancestors<'a, 'b>(
+    processes: &'b HashMap<u32, Process<'a>>,
+    pid: u32,
+) -> impl Iterator<Item = &'a str> + 'b {
+    let mut pid = Some(pid);
+    std::iter::from_fn(move || {
+        let parent = processes.get(&pid?)?.parent?;
+        pid = Some(parent);
+        Some(processes.get(&parent)?.name)
+    })
+}
+
+/// The version of a terminal from its `--version` output
+pub fn version(terminal: &str, deadline: Instant) -> Option<String> {
+    if !FONTS.iter().any(|(name, _)| *name == terminal) {
+        return None;
+    }
+    desktop::parse_version(&command::run(
+        Command::new(terminal).arg("--version"),
+        deadline,
+    )?)
+}
+
+/// Reads the font of a terminal relative to the home and config directory
+type ReadFont = fn(&Path, &Path) -> Option<String>;
+
+/// Terminals with a known version flag and the function reading their configured font
+const FONTS: &[(&str, ReadFont)] = &[
+    ("alacritty", alacritty_font),
+    ("kitty", kitty_font),
+    ("foot", foot_font),
+    ("wezterm", wezterm_font),
+    ("konsole", konsole_font),
+    ("gnome-terminal", |_, _| None),
+];
+
+/// The font configured for a terminal, read from its config file
+///
+/// The font of gnome-terminal is stored in the binary dconf database and is not read.
+pub fn font(terminal: &str, home: &Path, config_dir: &Path) -> Option<String> {
+    let (_, read) = FONTS.iter().find(|(name, _)| *name == terminal)?;
+    read(home, config_dir)
+}
+
+fn with_size(family: &str, size: Option<&str>) -> String {
+    match size {
+        Some(size) => format!("{family} {size}"),
+        None => family.to_string(),
+    }
+}
+
+fn alacritty_font(_home: &Path, config_dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(config_dir.join("alacritty/alacritty.toml")).ok()?;
+    let config: toml::Value = toml::from_str(&config).ok()?;
+    let font = config.get("font")?;
+    let family = font.get("normal")?.get("family")?.as_str()?;
+    let size = font.get("size").map(|size| size.to_string());
+    Some(with_size(family, size.as_deref()))
+}
+
+fn kitty_font(_home: &Path, config_dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(config_dir.join("kitty/kitty.conf")).ok()?;
+    let value = |key: &str| {
+        config.lines().find_map(|line| {
+            let (k, value) = line.trim().split_once(char::is_whitespace)?;
+            (k == key).then(|| value.trim())
+        })
+    };
+    Some(with_size(value("font_family")?, value("font_size")))
+}
+
+fn foot_font(_home: &Path, config_dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(config_dir.join("foot/foot.ini")).ok()?;
+    // the font is set in the `main` section, which may also be left out at the start
+    let font = config
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('[') || *line == "[main]")
+        .find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "font").then(|| value.trim())
+        })?;
+    // fonts are given like `Fira Code:size=11`, possibly with fallbacks separated by commas
+    let font = font.split(',').next()?;
+    let mut parts = font.split(':');
+    let family = parts.next()?;
+    let size = parts.find_map(|part| part.strip_prefix("size="));
+    Some(with_size(family, size))
+}
+
+fn wezterm_font(home: &Path, config_dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(home.join(".wezterm.lua"))
+        .or_else(|_| fs::read_to_string(config_dir.join("wezterm/wezterm.lua")))
+        .ok()?;
+    let (_, rest) = config.split_once("wezterm.font")?;
+    let family = rest.split(['"', '\'']).nth(1)?;
+    let size = config.split_once("font_size").and_then(|(_, rest)| {
+        let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+        rest.split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()
+            .filter(|size| !size.is_empty())
+    });
+    Some(with_size(family, size))
+}
+
+fn konsole_font(home: &Path, config_dir: &Path) -> Option<String> {
+    let konsolerc = fs::read_to_string(config_dir.join("konsolerc")).ok()?;
+    let profile = theme::ini_value(&konsolerc, "Desktop Entry", "DefaultProfile")?;
+    let profile = fs::read_to_string(home.join(".local/share/konsole").join(profile)).ok()?;
+    Some(theme::parse_qt_font(theme::ini_value(
+        &profile,
+        "Appearance",
+        "Font",
+    )?))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Processes as their PID, name, parent and arguments
+    pub(crate) type Tree = &'static [(u32, &'static str, Option<u32>, &'static [&'static str])];
+
+    /// Builds the process tree `tree` describes and passes it to `f`
+    pub(crate) fn with_processes<R>(tree: Tree, f: impl FnOnce(&HashMap<u32, Process>) -> R) -> R {
+        let cmds: Vec<Vec<String>> = tree
+            .iter()
+            .map(|(_, _, _, cmd)| cmd.iter().map(|arg| arg.to_string()).collect())
+            .collect();
+        let processes: HashMap<u32, Process> = tree
+            .iter()
+            .zip(&cmds)
+            .map(|((pid, name, parent, _), cmd)| {
+                (
+                    *pid,
+                    Process {
+                        name,
+                        parent: *parent,
+                        cmd,
+                    },
+                )
+            })
+            .collect();
+        f(&processes)
+    }
+
+    fn find_in(
+        tree: Tree,
+        client: impl Fn(&HashMap<u32, Process>, &str) -> Option<u32>,
+    ) -> Option<String> {
+        with_processes(tree, |processes| {
+            find(processes, 1000, |multiplexer| {
+                client(processes, multiplexer)
+            })
+            .map(str::to_string)
+        })
+    }
+
+    fn no_client(_: &HashMap<u32, Process>, _: &str) -> Option<u32> {
+        None
+    }
+
+    #[test]
+    fn walks_past_shells_and_wrappers() {
+        let cases: &[(Tree, Option<&str>)] = &[
+            (
+                &[
+                    (1, "systemd", None, &[]),
+                    (10, "alacritty", Some(1), &[]),
+                    (20, "zsh", Some(10), &[]),
+                    (1000, "pixfetch", Some(20), &[]),
+                ],
+                Some("alacritty"),
+            ),
+            (
+                &[
+                    (1, "systemd", None, &[]),
+                    (10, "kitty", Some(1), &[]),
+                    (20, "bash", Some(10), &[]),
+                    (30, "su", Some(20), &[]),
+                    (40, "fish", Some(30), &[]),
+                    (50, "sudo", Some(40), &[]),
+                    (1000, "pixfetch", Some(50), &[]),
+                ],
+                Some("kitty"),
+            ),
+            (
+                &[
+                    (1, "systemd", None, &[]),
+                    (10, "gnome-terminal-", Some(1), &[]),
+                    (20, "bash", Some(10), &[]),
+                    (1000, "pixfetch", Some(20), &[]),
+                ],
+                Some("gnome-terminal"),
+            ),
+        ];
+        for (tree, expected) in cases {
+            assert_eq!(find_in(tree, no_client).as_deref(), *expected);
+        }
+    }
+
+    #[test]
+    fn electron_below_code_is_vscode() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "code", Some(1), &[]),
+            (20, "electron", Some(10), &[]),
+            (30, "bash", Some(20), &[]),
+            (1000, "pixfetch", Some(30), &[]),
+        ];
+        assert_eq!(find_in(tree, no_client).as_deref(), Some("vscode"));
+    }
+
+    #[test]
+    fn stops_at_pid_1() {
+        let cases: &[Tree] = &[
+            &[
+                (1, "systemd", None, &[]),
+                (20, "bash", Some(1), &[]),
+                (1000, "pixfetch", Some(20), &[]),
+            ],
+            &[
+                (1, "init", None, &[]),
+                (20, "sudo", Some(1), &[]),
+                (1000, "pixfetch", Some(20), &[]),
+            ],
+            &[(20, "bash", None, &[]), (1000, "pixfetch", Some(20), &[])],
+        ];
+        for tree in cases {
+            assert_eq!(find_in(tree, no_client), None);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_sshd() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "sshd", Some(1), &[]),
+            (20, "sshd-session", Some(10), &[]),
+            (30, "bash", Some(20), &[]),
+            (1000, "pixfetch", Some(30), &[]),
+        ];
+        assert_eq!(find_in(tree, no_client).as_deref(), Some("sshd"));
+    }
+
+    #[test]
+    fn jumps_from_tmux_server_to_client() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "foot", Some(1), &[]),
+            (20, "bash", Some(10), &[]),
+            (30, "tmux: client", Some(20), &[]),
+            (40, "tmux: server", Some(1), &[]),
+            (50, "zsh", Some(40), &[]),
+            (1000, "pixfetch", Some(50), &[]),
+        ];
+        let tmux_client =
+            |_: &HashMap<u32, Process>, multiplexer: &str| (multiplexer == "tmux").then_some(30);
+        assert_eq!(find_in(tree, tmux_client).as_deref(), Some("foot"));
+        // without a client only the multiplexer is known
+        assert_eq!(find_in(tree, no_client).as_deref(), Some("tmux"));
+    }
+
+    #[test]
+    fn jumps_from_tmux_over_ssh() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "sshd", Some(1), &[]),
+            (20, "bash", Some(10), &[]),
+            (30, "tmux: client", Some(20), &[]),
+            (40, "tmux: server", Some(1), &[]),
+            (50, "zsh", Some(40), &[]),
+            (1000, "pixfetch", Some(50), &[]),
+        ];
+        let tmux_client =
+            |_: &HashMap<u32, Process>, multiplexer: &str| (multiplexer == "tmux").then_some(30);
+        assert_eq!(find_in(tree, tmux_client).as_deref(), Some("sshd"));
+    }
+
+    #[test]
+    fn jumps_from_screen_server_to_client() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "konsole", Some(1), &[]),
+            (20, "zsh", Some(10), &[]),
+            (30, "screen", Some(20), &["screen", "-r", "1234"]),
+            (40, "xterm", Some(1), &[]),
+            (50, "bash", Some(40), &[]),
+            (60, "screen", Some(50), &["screen", "-r", "5678"]),
+            (1234, "SCREEN", Some(1), &["SCREEN"]),
+            (1100, "bash", Some(1234), &[]),
+            (1000, "pixfetch", Some(1100), &[]),
+        ];
+        let screen_client = |processes: &HashMap<u32, Process>, multiplexer: &str| {
+            client(processes, multiplexer, "1234.pts-0.host")
+        };
+        assert_eq!(find_in(tree, screen_client).as_deref(), Some("konsole"));
+        assert_eq!(find_in(tree, no_client).as_deref(), Some("screen"));
+    }
+
+    #[test]
+    fn jumps_from_zellij_server_to_client() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "xterm", Some(1), &[]),
+            (20, "bash", Some(10), &[]),
+            (30, "zellij", Some(20), &["zellij", "attach", "other"]),
+            (40, "wezterm-gui", Some(1), &[]),
+            (50, "fish", Some(40), &[]),
+            (60, "zellij", Some(50), &["zellij", "attach", "dev"]),
+            (
+                70,
+                "zellij",
+                Some(1),
+                &["zellij", "--server", "/run/user/1000/zellij/dev"],
+            ),
+            (80, "fish", Some(70), &[]),
+            (1000, "pixfetch", Some(80), &[]),
+        ];
+        let zellij_client = |processes: &HashMap<u32, Process>, multiplexer: &str| {
+            client(processes, multiplexer, "dev")
+        };
+        assert_eq!(find_in(tree, zellij_client).as_deref(), Some("wezterm"));
+    }
+
+    #[test]
+    fn client_without_session_argument() {
+        let tree: Tree = &[
+            (1, "systemd", None, &[]),
+            (10, "alacritty", Some(1), &[]),
+            (20, "bash", Some(10), &[]),
+            (30, "zellij", Some(20), &["zellij"]),
+            (
+                70,
+                "zellij",
+                Some(1),
+                &["zellij", "--server", "/run/user/1000/zellij/dev"],
+            ),
+            (80, "bash", Some(70), &[]),
+            (1000, "pixfetch", Some(80), &[]),
+        ];
+        let zellij_client = |processes: &HashMap<u32, Process>, multiplexer: &str| {
+            client(processes, multiplexer, "dev")
+        };
+        assert_eq!(find_in(tree, zellij_client).as_deref(), Some("alacritty"));
+    }
+}
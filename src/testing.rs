@@ -0,0 +1,37 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A temporary directory standing in for the root or another directory in tests, which is
+/// removed again when dropped
+pub struct FakeRoot(PathBuf);
+
+impl FakeRoot {
+    /// Creates an empty directory, `name` must be unique among all tests
+    pub fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("pixfetch-test-{}-{name}", process::id()));
+        fs::remove_dir_all(&path).ok();
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Writes a file relative to the directory, creating its parent directories
+    pub fn file(&self, path: &str, contents: impl AsRef<[u8]>) -> &Self {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for FakeRoot {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
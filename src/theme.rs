@@ -0,0 +1,300 @@
+use std::{fs, path::Path};
+
+/// A setting in a config file of a toolkit
+///
+/// Files starting with `~/` are relative to the home directory, all others are relative to the
+/// config directory. Without a section the file is read in the `key = "value"` format of
+/// `.gtkrc-2.0`, otherwise as an INI file.
+pub struct Source {
+    toolkit: &'static str,
+    file: &'static str,
+    section: Option<&'static str>,
+    key: &'static str,
+    parse: fn(&str) -> String,
+}
+
+impl Source {
+    const fn ini(
+        toolkit: &'static str,
+        file: &'static str,
+        section: &'static str,
+        key: &'static str,
+    ) -> Self {
+        Self {
+            toolkit,
+            file,
+            section: Some(section),
+            key,
+            parse: str::to_string,
+        }
+    }
+
+    const fn gtkrc(key: &'static str) -> Self {
+        Self {
+            toolkit: "GTK2",
+            file: "~/.gtkrc-2.0",
+            section: None,
+            key,
+            parse: str::to_string,
+        }
+    }
+
+    const fn with_parse(mut self, parse: fn(&str) -> String) -> Self {
+        self.parse = parse;
+        self
+    }
+
+    fn read(&self, home: &Path, config_dir: &Path) -> Option<String> {
+        let path = match self.file.strip_prefix("~/") {
+            Some(file) => home.join(file),
+            None => config_dir.join(self.file),
+        };
+        let contents = fs::read_to_string(path).ok()?;
+        let value = match self.section {
+            Some(section) => ini_value(&contents, section, self.key),
+            None => gtkrc_value(&contents, self.key),
+        }?;
+        Some((self.parse)(value)).filter(|value| !value.is_empty())
+    }
+}
+
+pub const THEME: &[Source] = &[
+    Source::ini("KDE", "kdeglobals", "KDE", "widgetStyle"),
+    Source::gtkrc("gtk-theme-name"),
+    Source::ini("GTK3", "gtk-3.0/settings.ini", "Settings", "gtk-theme-name"),
+    Source::ini("GTK4", "gtk-4.0/settings.ini", "Settings", "gtk-theme-name"),
+];
+
+pub const ICONS: &[Source] = &[
+    Source::ini("KDE", "kdeglobals", "Icons", "Theme"),
+    Source::gtkrc("gtk-icon-theme-name"),
+    Source::ini(
+        "GTK3",
+        "gtk-3.0/settings.ini",
+        "Settings",
+        "gtk-icon-theme-name",
+    ),
+    Source::ini(
+        "GTK4",
+        "gtk-4.0/settings.ini",
+        "Settings",
+        "gtk-icon-theme-name",
+    ),
+];
+
+pub const CURSOR: &[Source] = &[
+    Source::ini("KDE", "kcminputrc", "Mouse", "cursorTheme"),
+    Source::gtkrc("gtk-cursor-theme-name"),
+    Source::ini(
+        "GTK3",
+        "gtk-3.0/settings.ini",
+        "Settings",
+        "gtk-cursor-theme-name",
+    ),
+    Source::ini(
+        "GTK4",
+        "gtk-4.0/settings.ini",
+        "Settings",
+        "gtk-cursor-theme-name",
+    ),
+    Source::ini(
+        "X11",
+        "~/.icons/default/index.theme",
+        "Icon Theme",
+        "Inherits",
+    ),
+];
+
+pub const FONT: &[Source] = &[
+    Source::ini("KDE", "kdeglobals", "General", "font").with_parse(parse_qt_font),
+    Source::gtkrc("gtk-font-name"),
+    Source::ini("GTK3", "gtk-3.0/settings.ini", "Settings", "gtk-font-name"),
+    Source::ini("GTK4", "gtk-4.0/settings.ini", "Settings", "gtk-font-name"),
+];
+
+/// Reads the value of a setting from all of its sources, returning the toolkits with their value
+pub fn read(sources: &[Source], home: &Path, config_dir: &Path) -> Vec<(&'static str, String)> {
+    sources
+        .iter()
+        .filter_map(|source| Some((source.toolkit, source.read(home, config_dir)?)))
+        .collect()
+}
+
+/// Formats the values of a setting like `Breeze [KDE], Adwaita [GTK2/3]`, listing toolkits with
+/// the same value together
+pub fn format(values: &[(&str, String)]) -> String {
+    let mut groups: Vec<(&str, Vec<&str>)> = vec![];
+    for (toolkit, value) in values {
+        match groups.iter_mut().find(|(v, _)| v == value) {
+            Some((_, toolkits)) => toolkits.push(toolkit),
+            None => groups.push((value, vec![toolkit])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(value, toolkits)| format!("{value} [{}]", join_toolkits(&toolkits)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Joins toolkits like `KDE/GTK2/3`, shortening GTK versions following another GTK version
+fn join_toolkits(toolkits: &[&str]) -> String {
+    let mut out = String::new();
+    for (index, toolkit) in toolkits.iter().enumerate() {
+        if index == 0 {
+            out.push_str(toolkit);
+            continue;
+        }
+        out.push('/');
+        match toolkit.strip_prefix("GTK") {
+            Some(version) if toolkits[index - 1].starts_with("GTK") => out.push_str(version),
+            _ => out.push_str(toolkit),
+        }
+    }
+    out
+}
+
+pub fn ini_value<'a>(contents: &'a str, section: &str, key: &str) -> Option<&'a str> {
+    let mut in_section = false;
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+        } else if in_section {
+            if let Some((k, value)) = line.split_once('=') {
+                if k.trim() == key {
+                    return Some(value.trim());
+                }
+            }
+        }
+    }
+    None
+}
+
+fn gtkrc_value<'a>(contents: &'a str, key: &str) -> Option<&'a str> {
+    contents.lines().find_map(|line| {
+        let (k, value) = line.split_once('=')?;
+        (k.trim() == key).then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Converts a Qt font description like `Noto Sans,10,-1,5,50,0,0,0,0,0` to `Noto Sans 10`
+pub fn parse_qt_font(font: &str) -> String {
+    let mut parts = font.split(',');
+    match (parts.next(), parts.next()) {
+        (Some(name), Some(size)) => format!("{name} {size}"),
+        _ => font.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn read_sources() {
+        let home = FakeRoot::new("theme-home");
+        let config = FakeRoot::new("theme-config");
+        home.file(".gtkrc-2.0", "gtk-theme-name=\"Adwaita\"\n")
+            .file(
+                ".icons/default/index.theme",
+                "[Icon Theme]\nInherits=Breeze\n",
+            );
+        config
+            .file(
+                "kdeglobals",
+                "[General]\nfont=Noto Sans,10,-1,5,50,0,0,0,0,0\n\n[KDE]\nwidgetStyle=Breeze\n",
+            )
+            .file(
+                "gtk-3.0/settings.ini",
+                "[Settings]\ngtk-theme-name = Adwaita\ngtk-font-name = \n",
+            );
+
+        assert_eq!(
+            read(THEME, home.path(), config.path()),
+            [
+                ("KDE", "Breeze".to_string()),
+                ("GTK2", "Adwaita".to_string()),
+                ("GTK3", "Adwaita".to_string()),
+            ]
+        );
+        assert_eq!(
+            read(FONT, home.path(), config.path()),
+            [("KDE", "Noto Sans 10".to_string())]
+        );
+        assert_eq!(
+            read(CURSOR, home.path(), config.path()),
+            [("X11", "Breeze".to_string())]
+        );
+        assert_eq!(read(ICONS, home.path(), config.path()), []);
+    }
+
+    #[test]
+    fn format_values() {
+        let values = |values: &[(&'static str, &str)]| {
+            values
+                .iter()
+                .map(|(toolkit, value)| (*toolkit, value.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            format(&values(&[
+                ("GTK2", "Adwaita"),
+                ("GTK3", "Adwaita"),
+                ("GTK4", "Adwaita"),
+            ])),
+            "Adwaita [GTK2/3/4]"
+        );
+        assert_eq!(
+            format(&values(&[("KDE", "Breeze"), ("GTK3", "Adwaita")])),
+            "Breeze [KDE], Adwaita [GTK3]"
+        );
+        assert_eq!(
+            format(&values(&[
+                ("KDE", "Breeze"),
+                ("GTK3", "Breeze"),
+                ("GTK4", "Breeze"),
+                ("X11", "Breeze"),
+            ])),
+            "Breeze [KDE/GTK3/4/X11]"
+        );
+        assert_eq!(
+            format(&values(&[("GTK2", "Adwaita"), ("X11", "Adwaita")])),
+            "Adwaita [GTK2/X11]"
+        );
+        assert_eq!(format(&[]), "");
+    }
+
+    #[test]
+    fn ini_values() {
+        let contents =
+            "[General]\ntheme=Other\n\n[Settings]\n; comment\ngtk-theme-name = Adwaita\n";
+        assert_eq!(
+            ini_value(contents, "Settings", "gtk-theme-name"),
+            Some("Adwaita")
+        );
+        assert_eq!(ini_value(contents, "General", "theme"), Some("Other"));
+        assert_eq!(ini_value(contents, "Settings", "theme"), None);
+        assert_eq!(ini_value(contents, "Missing", "theme"), None);
+    }
+
+    #[test]
+    fn gtkrc_values() {
+        let contents = "include \"/usr/share/themes/x\"\ngtk-theme-name=\"Adwaita\"\ngtk-icon-theme-name = Papirus\n";
+        assert_eq!(gtkrc_value(contents, "gtk-theme-name"), Some("Adwaita"));
+        assert_eq!(
+            gtkrc_value(contents, "gtk-icon-theme-name"),
+            Some("Papirus")
+        );
+        assert_eq!(gtkrc_value(contents, "gtk-font-name"), None);
+    }
+
+    #[test]
+    fn qt_fonts() {
+        assert_eq!(
+            parse_qt_font("Noto Sans,10,-1,5,50,0,0,0,0,0"),
+            "Noto Sans 10"
+        );
+        assert_eq!(parse_qt_font("Hack"), "Hack");
+    }
+}
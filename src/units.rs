@@ -0,0 +1,125 @@
+use crate::cli::UnitSystem;
+
+const IEC_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+const SI_UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB", "PB"];
+
+impl UnitSystem {
+    /// The number of bytes in one KiB or kB
+    pub fn base(&self) -> u64 {
+        match self {
+            UnitSystem::Iec => 1024,
+            UnitSystem::Si => 1000,
+        }
+    }
+
+    /// The unit names from bytes upwards
+    pub fn units(&self) -> &'static [&'static str] {
+        match self {
+            UnitSystem::Iec => IEC_UNITS,
+            UnitSystem::Si => SI_UNITS,
+        }
+    }
+}
+
+/// Converts a size in bytes to the largest unit of which there is at least one
+pub fn scale(bytes: u64, system: UnitSystem) -> (f64, &'static str) {
+    let base = system.base() as f64;
+    let units = system.units();
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+    (value, units[unit])
+}
+
+/// Formats a size in bytes with an automatically chosen unit, like `1.50 GiB`
+pub fn format_size(bytes: u64, system: UnitSystem) -> String {
+    match scale(bytes, system) {
+        (value, "B") => format!("{value} B"),
+        (value, unit) => format!("{value:.2} {unit}"),
+    }
+}
+
+/// The percentage of `total` that is `used`, or 0 when `total` is 0
+pub fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
+
+/// Formats the usage of some resource, like `1.50 GiB / 8.00 GiB (19%)`
+pub fn format_usage(used: u64, total: u64, system: UnitSystem, show_percent: bool) -> String {
+    let mut out = format!(
+        "{} / {}",
+        format_size(used, system),
+        format_size(total, system)
+    );
+    if show_percent {
+        out += &format!(" ({:.0}%)", percent(used, total));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_edges() {
+        assert_eq!(scale(1023, UnitSystem::Iec), (1023.0, "B"));
+        assert_eq!(scale(1024, UnitSystem::Iec), (1.0, "KiB"));
+        assert_eq!(scale(999, UnitSystem::Si), (999.0, "B"));
+        assert_eq!(scale(1000, UnitSystem::Si), (1.0, "kB"));
+        assert_eq!(scale(1000, UnitSystem::Iec), (1000.0, "B"));
+        assert_eq!(scale(1024, UnitSystem::Si), (1.024, "kB"));
+        assert_eq!(scale(3 << 30, UnitSystem::Iec), (3.0, "GiB"));
+    }
+
+    #[test]
+    fn scale_caps_at_pib() {
+        assert_eq!(scale(1 << 60, UnitSystem::Iec), (1024.0, "PiB"));
+        assert_eq!(scale(10u64.pow(18), UnitSystem::Si), (1000.0, "PB"));
+        assert_eq!(scale(u64::MAX, UnitSystem::Iec).1, "PiB");
+    }
+
+    #[test]
+    fn format_size_edges() {
+        assert_eq!(format_size(0, UnitSystem::Iec), "0 B");
+        assert_eq!(format_size(1023, UnitSystem::Iec), "1023 B");
+        assert_eq!(format_size(1024, UnitSystem::Iec), "1.00 KiB");
+        assert_eq!(format_size(999, UnitSystem::Si), "999 B");
+        assert_eq!(format_size(1000, UnitSystem::Si), "1.00 kB");
+        assert_eq!(format_size(1536 << 20, UnitSystem::Iec), "1.50 GiB");
+        assert_eq!(format_size(1 << 60, UnitSystem::Iec), "1024.00 PiB");
+    }
+
+    #[test]
+    fn percent_of_zero() {
+        assert_eq!(percent(0, 0), 0.0);
+        assert_eq!(percent(5, 0), 0.0);
+        assert_eq!(percent(1, 4), 25.0);
+    }
+
+    #[test]
+    fn format_usage_percent() {
+        let used = 1536 << 20;
+        let total = 8 << 30;
+        assert_eq!(
+            format_usage(used, total, UnitSystem::Iec, false),
+            "1.50 GiB / 8.00 GiB"
+        );
+        assert_eq!(
+            format_usage(used, total, UnitSystem::Iec, true),
+            "1.50 GiB / 8.00 GiB (19%)"
+        );
+        assert_eq!(
+            format_usage(500, 1000, UnitSystem::Si, true),
+            "500 B / 1.00 kB (50%)"
+        );
+        assert_eq!(format_usage(0, 0, UnitSystem::Si, true), "0 B / 0 B (0%)");
+    }
+}
@@ -0,0 +1,70 @@
+use std::{fs, path::Path};
+
+/// The size of a `struct utmp` record on Linux
+const UTMP_SIZE: usize = 384;
+/// The `ut_type` of a logged-in user session
+const USER_PROCESS: i16 = 7;
+/// The offset and length of `ut_user` in a record
+const USER_FIELD: (usize, usize) = (44, 32);
+
+/// The names of the users with a login session, one per session, read from the utmp file
+/// relative to the root directory
+pub fn logged_in(root: &Path) -> Option<Vec<String>> {
+    let utmp = ["run/utmp", "var/run/utmp"]
+        .iter()
+        .find_map(|path| fs::read(root.join(path)).ok())?;
+    Some(
+        utmp.chunks_exact(UTMP_SIZE)
+            .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == USER_PROCESS)
+            .filter_map(|record| {
+                let (offset, len) = USER_FIELD;
+                let user = &record[offset..offset + len];
+                let end = user.iter().position(|b| *b == 0).unwrap_or(len);
+                let user = String::from_utf8_lossy(&user[..end]).into_owned();
+                (!user.is_empty()).then_some(user)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    fn record(kind: i16, user: &str) -> Vec<u8> {
+        let mut record = vec![0; UTMP_SIZE];
+        record[..2].copy_from_slice(&kind.to_ne_bytes());
+        let (offset, _) = USER_FIELD;
+        record[offset..offset + user.len()].copy_from_slice(user.as_bytes());
+        record
+    }
+
+    #[test]
+    fn user_sessions() {
+        let root = FakeRoot::new("users-utmp");
+        assert_eq!(logged_in(root.path()), None);
+
+        // a boot time and a login process record, then two sessions of alice and one of bob
+        let utmp = [
+            record(2, "reboot"),
+            record(6, "LOGIN"),
+            record(USER_PROCESS, "alice"),
+            record(USER_PROCESS, "bob"),
+            record(8, "carol"),
+            record(USER_PROCESS, "alice"),
+            record(USER_PROCESS, "a-user-name-of-exactly-32-bytes!"),
+        ]
+        .concat();
+        root.file("var/run/utmp", utmp);
+        assert_eq!(
+            logged_in(root.path()),
+            Some(vec![
+                "alice".to_string(),
+                "bob".to_string(),
+                "alice".to_string(),
+                "a-user-name-of-exactly-32-bytes!".to_string(),
+            ])
+        );
+    }
+}
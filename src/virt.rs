@@ -0,0 +1,166 @@
+use std::{fs, path::Path};
+
+/// Hypervisors by a part of the DMI vendor or product name they report
+const HYPERVISORS: &[(&str, &str)] = &[
+    ("KVM", "KVM"),
+    ("QEMU", "QEMU"),
+    ("VMware", "VMware"),
+    ("innotek", "VirtualBox"),
+    ("VirtualBox", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Parallels", "Parallels"),
+    ("Bochs", "Bochs"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("Google Compute Engine", "Google Compute Engine"),
+    ("OpenStack", "OpenStack"),
+    ("bhyve", "bhyve"),
+];
+
+/// Container managers by a part of the cgroup path of the init process
+const CGROUPS: &[(&str, &str)] = &[
+    ("/docker", "Docker"),
+    ("/libpod", "Podman"),
+    ("/lxc", "LXC"),
+    ("kubepods", "Kubernetes"),
+    ("machine.slice", "systemd-nspawn"),
+];
+
+/// Detects the container pixfetch is running in, relative to the root directory
+pub fn container(root: &Path) -> Option<&'static str> {
+    if root.join(".dockerenv").exists() {
+        return Some("Docker");
+    }
+    if root.join("run/.containerenv").exists() {
+        return Some("Podman");
+    }
+    // set by systemd-nspawn and most other container managers
+    if let Ok(container) = fs::read_to_string(root.join("run/systemd/container")) {
+        return Some(match container.trim() {
+            "systemd-nspawn" => "systemd-nspawn",
+            "docker" => "Docker",
+            "podman" => "Podman",
+            "lxc" | "lxc-libvirt" => "LXC",
+            "wsl" => "WSL",
+            _ => "Container",
+        });
+    }
+    let cgroup = fs::read_to_string(root.join("proc/1/cgroup")).ok()?;
+    CGROUPS
+        .iter()
+        .find(|(path, _)| cgroup.contains(path))
+        .map(|(_, name)| *name)
+}
+
+/// Detects the hypervisor of the virtual machine pixfetch is running in, relative to the root
+/// directory
+///
+/// WSL is detected by the kernel version, all other hypervisors by their DMI information. A
+/// virtual machine with an unknown hypervisor is only noticed by the `hypervisor` CPU flag.
+pub fn hypervisor(root: &Path) -> Option<&'static str> {
+    if let Ok(version) = fs::read_to_string(root.join("proc/version")) {
+        if version.contains("WSL2") {
+            return Some("WSL2");
+        }
+        if version.contains("Microsoft") || version.contains("microsoft") {
+            return Some("WSL");
+        }
+    }
+
+    let dmi = ["sys_vendor", "product_name", "board_vendor", "bios_vendor"]
+        .iter()
+        .filter_map(|file| {
+            fs::read_to_string(root.join("sys/devices/virtual/dmi/id").join(file)).ok()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let Some((_, name)) = HYPERVISORS.iter().find(|(id, _)| dmi.contains(id)) {
+        return Some(name);
+    }
+    if dmi.contains("Microsoft Corporation") && dmi.contains("Virtual Machine") {
+        return Some("Hyper-V");
+    }
+
+    let cpuinfo = fs::read_to_string(root.join("proc/cpuinfo")).ok()?;
+    cpuinfo
+        .lines()
+        .any(|line| line.starts_with("flags") && line.split_whitespace().any(|f| f == "hypervisor"))
+        .then_some("VM")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeRoot;
+
+    #[test]
+    fn containers() {
+        let root = FakeRoot::new("virt-none");
+        assert_eq!(container(root.path()), None);
+        root.file("proc/1/cgroup", "0::/init.scope\n");
+        assert_eq!(container(root.path()), None);
+
+        let cases: &[(&str, &str, &str)] = &[
+            (".dockerenv", "", "Docker"),
+            ("run/.containerenv", "", "Podman"),
+            (
+                "run/systemd/container",
+                "systemd-nspawn\n",
+                "systemd-nspawn",
+            ),
+            ("run/systemd/container", "lxc\n", "LXC"),
+            ("run/systemd/container", "oci\n", "Container"),
+            (
+                "proc/1/cgroup",
+                "0::/kubepods/besteffort/pod1234/abcd\n",
+                "Kubernetes",
+            ),
+            ("proc/1/cgroup", "12:pids:/docker/0123abcd\n", "Docker"),
+        ];
+        for (index, (file, contents, expected)) in cases.iter().enumerate() {
+            let root = FakeRoot::new(&format!("virt-container-{index}"));
+            root.file(file, contents);
+            assert_eq!(container(root.path()), Some(*expected), "{file}");
+        }
+    }
+
+    #[test]
+    fn hypervisors() {
+        let root = FakeRoot::new("virt-bare-metal");
+        root.file("sys/devices/virtual/dmi/id/sys_vendor", "LENOVO\n")
+            .file("sys/devices/virtual/dmi/id/product_name", "20XW0055GE\n")
+            .file("proc/cpuinfo", "flags\t\t: fpu vme de pse tsc\n")
+            .file("proc/version", "Linux version 6.8.0-31-generic\n");
+        assert_eq!(hypervisor(root.path()), None);
+
+        const DMI: &str = "sys/devices/virtual/dmi/id";
+        let cases: &[(&[(&str, &str)], &str)] = &[
+            (&[("sys_vendor", "QEMU\n")], "QEMU"),
+            (&[("product_name", "VirtualBox\n")], "VirtualBox"),
+            (
+                &[
+                    ("sys_vendor", "Microsoft Corporation\n"),
+                    ("product_name", "Virtual Machine\n"),
+                ],
+                "Hyper-V",
+            ),
+        ];
+        for (index, (files, expected)) in cases.iter().enumerate() {
+            let root = FakeRoot::new(&format!("virt-dmi-{index}"));
+            for (file, contents) in *files {
+                root.file(&format!("{DMI}/{file}"), contents);
+            }
+            assert_eq!(hypervisor(root.path()), Some(*expected));
+        }
+
+        let root = FakeRoot::new("virt-wsl");
+        root.file(
+            "proc/version",
+            "Linux version 5.15.146.1-microsoft-standard-WSL2\n",
+        );
+        assert_eq!(hypervisor(root.path()), Some("WSL2"));
+
+        let root = FakeRoot::new("virt-unknown-hypervisor");
+        root.file("proc/cpuinfo", "flags\t\t: fpu vme hypervisor lahf_lm\n");
+        assert_eq!(hypervisor(root.path()), Some("VM"));
+    }
+}
@@ -22,7 +22,7 @@ pub fn main() -> Result<()> {
 
         let status = Command::new("cross")
             .current_dir(project_root())
-            .args(&["build", "--release", "--target", target])
+            .args(["build", "--release", "--target", target])
             .status()?;
         if !status.success() {
             Err(format!("cargo build for target {target} failed"))?;
@@ -91,7 +91,7 @@ fn out_dir(target: &str) -> Result<PathBuf> {
     let bin = *crate::BIN;
 
     let mut manpages = fs::read_dir(
-        &project_root()
+        project_root()
             .join("target")
             .join(target)
             .join("release/build"),